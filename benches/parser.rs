@@ -1,4 +1,4 @@
-use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
 use std::{fs, hint::black_box, path::Path};
 
 fn parse_helm_values(c: &mut Criterion) {
@@ -39,9 +39,75 @@ fn parse_helm_values(c: &mut Criterion) {
     group.finish();
 }
 
+fn parse_empty_input(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_empty_input");
+
+    // Whitespace-only input returns before a parser is created, whereas a lone
+    // comment must still be parsed
+    for (name, content) in [("whitespace", "  \n\t\n  "), ("comment", "# comment\n")] {
+        group.bench_with_input(BenchmarkId::from_parameter(name), content, |b, content| {
+            b.iter(|| {
+                let doc = yam::parser::parse(black_box(content)).expect("parsing should not fail");
+                black_box(doc);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn parse_wide_flow_sequence(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_wide_flow_sequence");
+
+    // Reported per element, so comparing the throughput of the two sizes shows
+    // whether parsing a flow sequence scales linearly with its length. The time
+    // covers both building the tree-sitter syntax tree and walking it into scalars
+    for size in [1000, 5000] {
+        let items: Vec<String> = (0..size).map(|n| n.to_string()).collect();
+        let content = format!("[{}]", items.join(", "));
+
+        group.throughput(Throughput::Elements(size));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &content, |b, content| {
+            b.iter(|| {
+                let doc = yam::parser::parse(black_box(content))
+                    .expect("parsing should not fail")
+                    .expect("document should not be empty");
+                black_box(doc);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn parse_single_line_flow_document(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_single_line_flow_document");
+
+    // Every node, and the trailing comment, sits on line 0, so any lookup keyed by
+    // line would show up here as throughput falling away with size
+    for size in [1000, 5000] {
+        let entries: Vec<String> = (0..size)
+            .map(|n| format!("key{n}: {{nested: [1, 2, 3]}}"))
+            .collect();
+        let content = format!("{{{}}} # settings", entries.join(", "));
+
+        group.throughput(Throughput::Bytes(content.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &content, |b, content| {
+            b.iter(|| {
+                let doc = yam::parser::parse(black_box(content))
+                    .expect("parsing should not fail")
+                    .expect("document should not be empty");
+                black_box(doc);
+            })
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default().measurement_time(std::time::Duration::from_secs(15));
-    targets = parse_helm_values
+    targets = parse_helm_values, parse_empty_input, parse_wide_flow_sequence, parse_single_line_flow_document
 }
 criterion_main!(benches);
@@ -1,3 +1,4 @@
 fn main() {
+    #[cfg(feature = "cli")]
     built::write_built_file().unwrap();
 }
@@ -0,0 +1,47 @@
+use crate::{
+    error::Result,
+    markdown::{Flattened, RenderOptions, collapse_cell, flatten_document, push_truncation_note},
+    parser::Document,
+};
+
+pub fn render_confluence(document: &Document<'_>, options: &RenderOptions) -> Result<String> {
+    let mut output = String::new();
+
+    if options.number_rows {
+        output.push_str("||#");
+    }
+    let labels = &options.labels;
+    output.push_str(&format!("||{}||{}||", labels.name, labels.value));
+    if options.show_required {
+        output.push_str(&format!("{}||", labels.required));
+    }
+    output.push_str(&format!("{}||\n", labels.description));
+
+    let Flattened { rows, omitted } = flatten_document(document, options);
+    for row in rows {
+        if options.number_rows {
+            output.push_str(&format!("|{}", row.index));
+        }
+        output.push_str(&format!(
+            "|{}|{}|",
+            escape_cell(&row.name),
+            escape_cell(&collapse_cell(&row.value, options))
+        ));
+        if options.show_required {
+            output.push_str(&format!("{}|", labels.required_text(row.required)));
+        }
+        output.push_str(&format!("{}|\n", escape_cell(&row.description)));
+    }
+
+    push_truncation_note(&mut output, omitted);
+    Ok(output)
+}
+
+// Confluence collapses empty cells into their neighbours, so they are padded
+// with a single space to keep the columns aligned. Line breaks are written as `\\`
+fn escape_cell(text: &str) -> String {
+    if text.is_empty() {
+        return " ".to_string();
+    }
+    text.replace('|', "\\|").replace('\n', "\\\\")
+}
@@ -0,0 +1,32 @@
+use crate::parser::ParseError;
+
+/// Errors returned from the public API of the library
+#[derive(Debug, thiserror::Error)]
+pub enum YamError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse template")]
+    Template(#[source] tera::Error),
+    #[error("failed to render template")]
+    Render(#[source] tera::Error),
+    #[error("failed to serialize rows")]
+    Serialize(#[from] serde_json::Error),
+    #[error("invalid JSON schema")]
+    Schema(#[source] Box<jsonschema::ValidationError<'static>>),
+    #[error("invalid redact pattern: {pattern}")]
+    Pattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+    #[error("invalid locale: {0}")]
+    Locale(String),
+    #[error("conflicting values for key {0} after exploding dotted keys")]
+    KeyConflict(String),
+    #[error("the list layout has no table rows to hand to a hook")]
+    ListLayoutHook,
+}
+
+pub type Result<T, E = YamError> = std::result::Result<T, E>;
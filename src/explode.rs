@@ -0,0 +1,126 @@
+use crate::{
+    error::{Result, YamError},
+    parser::{Definition, Document, MapItem, Scalar, ScalarType},
+};
+
+/// Returns a copy of the document with every dotted key, `a.b.c: 1`, split into
+/// nested maps, so that it documents identically to the nested form. Keys that
+/// share a prefix are merged into the same map
+pub fn explode_keys<'a>(document: &Document<'a>) -> Result<Document<'a>> {
+    let definitions = document
+        .definitions
+        .iter()
+        .map(|definition| {
+            Ok(Definition {
+                name: definition.name,
+                value: explode_scalar(&definition.value, definition.name)?,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(Document {
+        root: explode_scalar(&document.root, "")?,
+        definitions,
+    })
+}
+
+fn explode_scalar<'a>(scalar: &Scalar<'a>, path: &str) -> Result<Scalar<'a>> {
+    let mut comment = scalar.comment.clone();
+    let value = match &scalar.value {
+        ScalarType::Map(map) => {
+            let mut items = Vec::new();
+            for entry in map {
+                let value = explode_scalar(&entry.value, &join(path, entry.key))?;
+                // Keys such as `.hidden` or `a..b` have no sensible nesting and are kept whole
+                let segments: Vec<&str> = if entry.key.split('.').any(str::is_empty) {
+                    vec![entry.key]
+                } else {
+                    entry.key.split('.').collect()
+                };
+                insert(&mut items, &segments, value, entry.bare_key, path)?;
+            }
+            // A comment shared with the first entry, as above a block map, moves with
+            // that entry, so it follows whichever entry now comes first
+            if let Some(first) = map.first()
+                && first.value.comment == scalar.comment
+            {
+                comment = items.first().and_then(|item| item.value.comment.clone());
+            }
+            ScalarType::Map(items)
+        }
+        ScalarType::List(list) => ScalarType::List(
+            list.iter()
+                .enumerate()
+                .map(|(index, item)| explode_scalar(item, &join(path, &index.to_string())))
+                .collect::<Result<_>>()?,
+        ),
+        leaf => leaf.clone(),
+    };
+
+    Ok(Scalar {
+        value,
+        comment,
+        explicit_default: scalar.explicit_default.clone(),
+        explicit_type: scalar.explicit_type.clone(),
+        required: scalar.required,
+        alias: scalar.alias,
+        style: scalar.style,
+    })
+}
+
+fn insert<'a>(
+    items: &mut Vec<MapItem<'a>>,
+    segments: &[&'a str],
+    value: Scalar<'a>,
+    bare_key: bool,
+    path: &str,
+) -> Result<()> {
+    let (key, rest) = segments
+        .split_first()
+        .expect("a key has at least one segment");
+    let path = join(path, key);
+
+    let value = if rest.is_empty() {
+        value
+    } else {
+        let mut nested = Vec::new();
+        insert(&mut nested, rest, value, bare_key, &path)?;
+        Scalar::new(ScalarType::Map(nested))
+    };
+
+    let Some(existing) = items.iter_mut().find(|item| item.key == *key) else {
+        items.push(MapItem {
+            key,
+            value,
+            bare_key: bare_key && rest.is_empty(),
+        });
+        return Ok(());
+    };
+
+    match (&mut existing.value.value, value.value) {
+        (ScalarType::Map(existing_map), ScalarType::Map(map)) => {
+            for entry in map {
+                insert(
+                    existing_map,
+                    &[entry.key],
+                    entry.value,
+                    entry.bare_key,
+                    &path,
+                )?;
+            }
+            if existing.value.comment.is_none() {
+                existing.value.comment = value.comment;
+            }
+            Ok(())
+        }
+        _ => Err(YamError::KeyConflict(path)),
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
@@ -0,0 +1,123 @@
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    error::Result,
+    markdown::{Flattened, RenderOptions, collapse_cell, flatten_document, push_truncation_note},
+    parser::Document,
+};
+
+/// The characters used to draw the borders of a grid
+struct Borders {
+    horizontal: char,
+    vertical: char,
+    top: [char; 3],
+    middle: [char; 3],
+    bottom: [char; 3],
+}
+
+const BOX_DRAWING: Borders = Borders {
+    horizontal: '─',
+    vertical: '│',
+    top: ['┌', '┬', '┐'],
+    middle: ['├', '┼', '┤'],
+    bottom: ['└', '┴', '┘'],
+};
+
+const ASCII: Borders = Borders {
+    horizontal: '-',
+    vertical: '|',
+    top: ['+', '+', '+'],
+    middle: ['+', '+', '+'],
+    bottom: ['+', '+', '+'],
+};
+
+/// Renders an aligned plain-text table for display within a terminal
+pub fn render_grid(document: &Document<'_>, options: &RenderOptions) -> Result<String> {
+    let borders = if options.ascii { &ASCII } else { &BOX_DRAWING };
+
+    let labels = &options.labels;
+    let mut header = vec![
+        labels.name.as_str(),
+        labels.value.as_str(),
+        labels.description.as_str(),
+    ];
+    if options.show_required {
+        header.insert(2, labels.required.as_str());
+    }
+    if options.number_rows {
+        header.insert(0, "#");
+    }
+
+    let mut table: Vec<Vec<String>> = vec![header.into_iter().map(String::from).collect()];
+    let Flattened { rows, omitted } = flatten_document(document, options);
+    for row in rows {
+        let mut cells = vec![
+            row.name,
+            single_line(&collapse_cell(&row.value, options)),
+            single_line(&row.description),
+        ];
+        if options.show_required {
+            cells.insert(2, labels.required_text(row.required).to_string());
+        }
+        if options.number_rows {
+            cells.insert(0, row.index.to_string());
+        }
+        table.push(cells);
+    }
+
+    let widths: Vec<usize> = (0..table[0].len())
+        .map(|column| {
+            table
+                .iter()
+                .map(|cells| cells[column].width())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut output = String::new();
+    push_border(&mut output, &widths, borders, borders.top);
+    for (index, cells) in table.iter().enumerate() {
+        push_cells(&mut output, cells, &widths, borders);
+        if index == 0 {
+            push_border(&mut output, &widths, borders, borders.middle);
+        }
+    }
+    push_border(&mut output, &widths, borders, borders.bottom);
+
+    push_truncation_note(&mut output, omitted);
+    Ok(output)
+}
+
+fn single_line(text: &str) -> String {
+    text.replace('\n', " ")
+}
+
+fn push_border(
+    output: &mut String,
+    widths: &[usize],
+    borders: &Borders,
+    [left, join, right]: [char; 3],
+) {
+    output.push(left);
+    for (index, width) in widths.iter().enumerate() {
+        if index > 0 {
+            output.push(join);
+        }
+        output.extend(std::iter::repeat_n(borders.horizontal, width + 2));
+    }
+    output.push(right);
+    output.push('\n');
+}
+
+fn push_cells(output: &mut String, cells: &[String], widths: &[usize], borders: &Borders) {
+    output.push(borders.vertical);
+    for (cell, width) in cells.iter().zip(widths) {
+        let padding = width - cell.width();
+        output.push(' ');
+        output.push_str(cell);
+        output.extend(std::iter::repeat_n(' ', padding + 1));
+        output.push(borders.vertical);
+    }
+    output.push('\n');
+}
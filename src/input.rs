@@ -0,0 +1,194 @@
+use std::{
+    io::{self, Read},
+    path::Path,
+};
+
+use flate2::read::GzDecoder;
+
+use crate::error::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum InputFormat {
+    /// A plain YAML document
+    Yaml,
+    /// A markdown file whose YAML front matter is documented
+    Frontmatter,
+    /// A gzip compressed YAML document
+    GzipYaml,
+    /// A dotenv file of `KEY=value` lines, documented as a flat map
+    Env,
+}
+
+impl InputFormat {
+    /// Detects the format of a file from its extension, falling back to YAML
+    pub fn from_path(path: &str) -> Self {
+        let path = Path::new(path);
+        // Dotenv files are usually named `.env` or `.env.local`, without an extension
+        let file_name = path.file_name().and_then(|name| name.to_str());
+        if file_name.is_some_and(|name| name == ".env" || name.starts_with(".env.")) {
+            return InputFormat::Env;
+        }
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("md" | "markdown") => InputFormat::Frontmatter,
+            Some("gz") => InputFormat::GzipYaml,
+            Some("env") => InputFormat::Env,
+            _ => InputFormat::Yaml,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Encoding {
+    /// UTF-8, rejecting any invalid byte sequence
+    #[default]
+    Utf8,
+    /// ISO-8859-1, where every byte is the Unicode code point of the same value
+    Latin1,
+    /// The Windows superset of Latin-1, with printable characters such as `€`
+    /// in place of the C1 control codes
+    #[value(name = "windows-1252")]
+    Windows1252,
+}
+
+impl Encoding {
+    /// Transcodes text in this encoding into UTF-8
+    pub fn decode(&self, bytes: Vec<u8>) -> io::Result<String> {
+        match self {
+            Encoding::Utf8 => {
+                String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Encoding::Latin1 => Ok(encoding_rs::mem::decode_latin1(&bytes).into_owned()),
+            // The five bytes Windows leaves undefined keep their Latin-1 control code
+            Encoding::Windows1252 => Ok(encoding_rs::WINDOWS_1252
+                .decode_without_bom_handling(&bytes)
+                .0
+                .into_owned()),
+        }
+    }
+}
+
+/// Decodes raw UTF-8 input into the YAML text to be documented. A markdown file
+/// without any front matter has nothing to document and decodes to an empty string
+pub fn decode_input(bytes: Vec<u8>, format: InputFormat) -> Result<String> {
+    decode_input_with_encoding(bytes, format, Encoding::Utf8)
+}
+
+/// Decodes raw input as [`decode_input`] does, first transcoding it from the given
+/// encoding. Compressed input is transcoded once decompressed
+pub fn decode_input_with_encoding(
+    bytes: Vec<u8>,
+    format: InputFormat,
+    encoding: Encoding,
+) -> Result<String> {
+    match format {
+        InputFormat::Yaml => Ok(encoding.decode(bytes)?),
+        InputFormat::Frontmatter => {
+            let text = encoding.decode(bytes)?;
+            Ok(extract_front_matter(&text).unwrap_or_default().to_string())
+        }
+        InputFormat::GzipYaml => {
+            let mut decompressed = Vec::new();
+            GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+            Ok(encoding.decode(decompressed)?)
+        }
+        InputFormat::Env => Ok(env_to_yaml(&encoding.decode(bytes)?)?),
+    }
+}
+
+// Each `KEY=value` line becomes a map entry holding its value as a string, while
+// comments and blank lines are kept so comments above a key still describe it
+fn env_to_yaml(text: &str) -> io::Result<String> {
+    let mut yaml = String::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            yaml.push_str(line);
+            yaml.push('\n');
+            continue;
+        }
+
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid env entry at line {}", number + 1),
+            )
+        };
+        let entry = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = entry.split_once('=').ok_or_else(invalid)?;
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c))
+        {
+            return Err(invalid());
+        }
+
+        let value = value.trim_start();
+        let (value, comment) = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let end = closing_quote(value, quote).ok_or_else(invalid)?;
+                let (quoted, rest) = value.split_at(end + 1);
+                let content = &quoted[1..quoted.len() - 1];
+                // A double quoted value keeps its escape sequences, which YAML shares
+                let value = if quote == '"' {
+                    quoted.to_string()
+                } else {
+                    double_quote(content)
+                };
+                (value, rest.trim())
+            }
+            _ => match value.find(" #") {
+                Some(index) => (double_quote(value[..index].trim()), value[index..].trim()),
+                None => (double_quote(value.trim()), ""),
+            },
+        };
+        if !comment.is_empty() && !comment.starts_with('#') {
+            return Err(invalid());
+        }
+
+        yaml.push_str(&format!("{}: {}", key, value));
+        if !comment.is_empty() {
+            yaml.push_str(&format!(" {}", comment));
+        }
+        yaml.push('\n');
+    }
+    Ok(yaml)
+}
+
+// The position of the quote ending a value that starts with one. Within double
+// quotes, a quote escaped with a backslash doesn't end the value
+fn closing_quote(value: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in value.char_indices().skip(1) {
+        match c {
+            '\\' if quote == '"' && !escaped => escaped = true,
+            c if c == quote && !escaped => return Some(index),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+fn double_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Returns the YAML enclosed between the opening `---` line of a markdown file and
+/// the next `---` or `...` line
+pub fn extract_front_matter(text: &str) -> Option<&str> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let rest = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if matches!(line.trim_end(), "---" | "...") {
+            return Some(&rest[..offset]);
+        }
+        offset += line.len();
+    }
+    None
+}
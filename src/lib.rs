@@ -1 +1,24 @@
+#[cfg(feature = "cli")]
+pub mod confluence;
+pub mod error;
+pub mod explode;
+#[cfg(feature = "cli")]
+pub mod grid;
+#[cfg(feature = "cli")]
+pub mod input;
+pub mod lint;
+pub mod locale;
+pub mod markdown;
 pub mod parser;
+#[cfg(feature = "cli")]
+pub mod properties;
+pub mod redact;
+pub mod report;
+pub mod schema;
+#[cfg(feature = "cli")]
+pub mod slack;
+pub mod units;
+#[cfg(feature = "cli")]
+pub mod xml;
+#[cfg(feature = "cli")]
+pub mod yaml;
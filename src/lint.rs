@@ -0,0 +1,66 @@
+use std::{collections::HashSet, fmt};
+
+use crate::parser::{Document, Scalar, ScalarType};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+pub fn lint_document(document: &Document<'_>) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    lint_definitions(document, &mut lints);
+    lint_scalar(&document.root, String::new(), &mut lints);
+    lints
+}
+
+// Each redefined anchor is reported once, however many times it is defined
+fn lint_definitions(document: &Document<'_>, lints: &mut Vec<Lint>) {
+    let mut seen = HashSet::new();
+    let mut reported = HashSet::new();
+    for definition in &document.definitions {
+        if !seen.insert(definition.name) && reported.insert(definition.name) {
+            lints.push(Lint {
+                path: format!("&{}", definition.name),
+                message: "anchor is defined more than once, aliases use the latest definition"
+                    .to_string(),
+            });
+        }
+    }
+}
+
+fn lint_scalar(scalar: &Scalar<'_>, path: String, lints: &mut Vec<Lint>) {
+    match &scalar.value {
+        ScalarType::Map(map) => {
+            for entry in map {
+                let new_path = if path.is_empty() {
+                    entry.key.to_string()
+                } else {
+                    format!("{}.{}", path, entry.key)
+                };
+
+                if entry.bare_key {
+                    lints.push(Lint {
+                        path: new_path.clone(),
+                        message: "key has no ':' separator and will be documented as null"
+                            .to_string(),
+                    });
+                }
+                lint_scalar(&entry.value, new_path, lints);
+            }
+        }
+        ScalarType::List(list) => {
+            for (index, item) in list.iter().enumerate() {
+                lint_scalar(item, format!("{}.{}", path, index), lints);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,93 @@
+use serde::Serialize;
+
+use crate::{
+    error::{Result, YamError},
+    markdown::format_scalar_value,
+    parser::{ScalarType, parse},
+};
+
+/// The words written by the table renderers, so that documentation can be generated
+/// in a language other than English
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Labels {
+    pub name: String,
+    pub value: String,
+    pub description: String,
+    pub required: String,
+    pub anchor: String,
+    /// Marks a required value within the required column
+    pub yes: String,
+    /// Marks an optional value within the required column
+    pub no: String,
+    pub true_value: String,
+    pub false_value: String,
+    pub null_value: String,
+}
+
+impl Default for Labels {
+    fn default() -> Self {
+        Self {
+            name: "Name".to_string(),
+            value: "Value".to_string(),
+            description: "Description".to_string(),
+            required: "Required".to_string(),
+            anchor: "Anchor".to_string(),
+            yes: "Yes".to_string(),
+            no: "No".to_string(),
+            true_value: "true".to_string(),
+            false_value: "false".to_string(),
+            null_value: "null".to_string(),
+        }
+    }
+}
+
+impl Labels {
+    /// The text written within the required column
+    pub fn required_text(&self, required: bool) -> &str {
+        if required { &self.yes } else { &self.no }
+    }
+
+    /// Reads label overrides from a YAML map, such as `description: Beschreibung`
+    /// or `true: ja`. Any label left out keeps its English default
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        let mut labels = Labels::default();
+        let Some(document) = parse(text)? else {
+            return Ok(labels);
+        };
+
+        let ScalarType::Map(map) = &document.root.value else {
+            return Err(YamError::Locale(
+                "expected a map of labels to their text".to_string(),
+            ));
+        };
+
+        for entry in map {
+            let text = match &entry.value.value {
+                ScalarType::Null | ScalarType::Map(_) | ScalarType::List(_) => {
+                    return Err(YamError::Locale(format!(
+                        "label {} must be written as text",
+                        entry.key
+                    )));
+                }
+                leaf => format_scalar_value(leaf),
+            };
+
+            let label = match entry.key {
+                "name" => &mut labels.name,
+                "value" => &mut labels.value,
+                "description" => &mut labels.description,
+                "required" => &mut labels.required,
+                "anchor" => &mut labels.anchor,
+                "yes" => &mut labels.yes,
+                "no" => &mut labels.no,
+                "true" => &mut labels.true_value,
+                "false" => &mut labels.false_value,
+                "null" => &mut labels.null_value,
+                key => return Err(YamError::Locale(format!("unknown label {}", key))),
+            };
+            *label = text;
+        }
+
+        Ok(labels)
+    }
+}
@@ -1,14 +1,34 @@
-mod markdown;
-mod parser;
-
-use anyhow::{Context, Result};
-use clap::Parser;
+use anyhow::{Context, Result, bail};
+use clap::{Parser, ValueEnum};
 use std::{
+    cell::Cell,
+    env,
+    fmt::Display,
     fs,
-    io::{self, Read},
+    io::{self, BufWriter, IsTerminal, Read, Write},
 };
 
-use crate::{markdown::render_markdown, parser::parse};
+use yam::{
+    confluence::render_confluence,
+    error::YamError,
+    explode::explode_keys,
+    grid::render_grid,
+    input::{Encoding, InputFormat, decode_input_with_encoding},
+    lint::lint_document,
+    locale::Labels,
+    markdown::{
+        EmptyContainer, IndexStyle, Layout, RenderOptions, SortOrder, document_heading,
+        render_markdown, render_markdown_minimal, render_markdown_stream, table_rows,
+    },
+    parser::{CommentStyle, Document, ParseOptions, parse_all_with_options},
+    properties::render_properties,
+    redact::{compile_patterns, redact_document},
+    report::{Summary, count_types, render_type_report},
+    schema::validate_document,
+    slack::render_slack,
+    xml::render_xml,
+    yaml::render_yaml,
+};
 
 pub mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
@@ -17,6 +37,64 @@ pub mod built_info {
 const LONG_ABOUT: &str = r#"A context-aware YAML to markdown document generator that parses YAML files
 and renders them as markdown tables."#;
 
+/// Inputs larger than this are streamed as markdown, rather than buffering every row
+const STREAM_THRESHOLD: usize = 16 * 1024 * 1024;
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum Format {
+    /// A markdown table
+    #[default]
+    Markdown,
+    /// A markdown table without the Description column
+    MarkdownMinimal,
+    /// A markdown table without any surrounding blank lines, for embedding
+    MarkdownTableOnly,
+    /// A Confluence wiki markup table
+    Confluence,
+    /// A plain-text table drawn with box-drawing characters
+    Grid,
+    /// A list formatted with Slack's mrkdwn, for posting to a channel
+    Slack,
+    /// The document as nested XML elements
+    Xml,
+    /// A Java properties file of dotted keys
+    Properties,
+    /// The document re-emitted as canonical YAML, with descriptions as comments
+    Yaml,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum ColorChoice {
+    /// Color output written to a terminal, unless NO_COLOR or CLICOLOR_FORCE say otherwise
+    #[default]
+    Auto,
+    /// Always color output
+    Always,
+    /// Never color output
+    Never,
+}
+
+impl ColorChoice {
+    // An explicit choice wins over the environment, which wins over detecting a terminal
+    fn enabled(self, stream: &impl IsTerminal) -> bool {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        let force =
+            env::var_os("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0");
+
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => !no_color && (force || stream.is_terminal()),
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Report {
+    /// Tally the leaf values of the document by type
+    Types,
+}
+
 #[derive(Parser, Debug)]
 #[command(
     author,
@@ -32,13 +110,269 @@ struct Args {
     #[arg(value_name = "FILE")]
     file: Option<String>,
 
+    /// How the input is read, rather than detecting it from the file extension
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    input_format: Option<InputFormat>,
+
+    /// The character encoding of the input, which is transcoded to UTF-8 before
+    /// parsing
+    #[arg(long, value_enum, value_name = "ENCODING", default_value_t)]
+    encoding: Encoding,
+
+    /// Which comments are treated as descriptions
+    #[arg(long, value_enum, value_name = "STYLE", default_value_t)]
+    comment_style: CommentStyle,
+
+    /// The marker prefixing a description when using the helm comment style
+    #[arg(long, value_name = "MARKER", default_value = "--")]
+    comment_marker: String,
+
+    /// Document the comments of a file that contains nothing but comments
+    #[arg(long)]
+    keep_comment_only: bool,
+
+    /// Mask the value of any key whose row name fully matches the regex,
+    /// e.g. '.*password.*'. Can be repeated
+    #[arg(long, value_name = "PATTERN")]
+    redact: Vec<String>,
+
+    /// Keep the line breaks of multi-line comments within descriptions
+    #[arg(long)]
+    preserve_comment_breaks: bool,
+
+    /// A caption written once above the generated output
+    #[arg(long, value_name = "TITLE")]
+    title: Option<String>,
+
+    /// Precede the markdown table with one listing each anchor and its value
+    #[arg(long)]
+    show_definitions: bool,
+
+    /// A Tera template file used to render the markdown table. Falls back to the
+    /// template held in the YAM_TEMPLATE environment variable when omitted
+    #[arg(long, value_name = "FILE")]
+    template: Option<String>,
+
+    /// Write each name within a markdown table as inline code
+    #[arg(long)]
+    code_keys: bool,
+
+    /// A YAML file overriding the words written within tables, such as column
+    /// headers and the text of booleans and nulls
+    #[arg(long, value_name = "FILE")]
+    locale: Option<String>,
+
+    /// Give each nested list of maps sharing the same keys its own markdown table,
+    /// with a column per key
+    #[arg(long)]
+    flatten_lists_of_maps_as_columns: bool,
+
+    /// Stop the table after N rows, noting how many were left out
+    #[arg(long, value_name = "N")]
+    max_rows: Option<usize>,
+
+    /// Only document the value at this dotted path, with rows named relative to it.
+    /// List indices are counted from --index-base
+    #[arg(long, value_name = "PATH")]
+    entry: Option<String>,
+
+    /// Split dotted keys, such as a.b.c, into nested maps before documenting them
+    #[arg(long)]
+    explode_keys: bool,
+
+    /// Sort the keys of every map alphabetically, at all levels, before documenting
+    /// them. Lists keep their order
+    #[arg(long)]
+    sort_maps: bool,
+
+    /// Join groups of comments separated by blank lines with a paragraph break
+    #[arg(long)]
+    merge_comment_groups: bool,
+
+    /// Fail when an anchor is defined more than once within a document, rather than
+    /// using the latest definition and reporting it through --lint
+    #[arg(long)]
+    strict_anchors: bool,
+
+    /// Treat dates and times, such as 2021-04-01, as plain strings
+    #[arg(long)]
+    no_timestamps: bool,
+
+    /// Output format of the generated document
+    #[arg(short, long, value_enum, default_value_t)]
+    format: Format,
+
+    /// Print the flattened table rows as a JSON array instead of rendering a document.
+    /// Multiple documents are written as an array holding the rows of each
+    #[arg(long, conflicts_with = "format")]
+    emit_rows_json: bool,
+
+    /// Write markdown rows as the document is walked, rather than buffering the
+    /// whole table. Always enabled for inputs larger than 16 MiB
+    #[arg(long)]
+    stream: bool,
+
+    /// Leave out any value whose comment contains the --internal-marker, along with
+    /// everything nested within it
+    #[arg(long)]
+    hide_internal: bool,
+
+    /// The marker identifying internal values hidden by --hide-internal
+    #[arg(long, value_name = "MARKER", default_value = "@internal")]
+    internal_marker: String,
+
+    /// Leave out the newline that otherwise ends the output
+    #[arg(long)]
+    no_trailing_newline: bool,
+
+    /// Draw the grid format using only ASCII characters
+    #[arg(long)]
+    ascii: bool,
+
+    /// How the markdown document is laid out
+    #[arg(long, value_enum, default_value_t)]
+    layout: Layout,
+
+    /// How list indices are written within a row name
+    #[arg(long, value_enum, value_name = "STYLE", default_value_t)]
+    index_style: IndexStyle,
+
+    /// The index given to the first item of a list within a row name, 0 or 1
+    #[arg(long, value_name = "BASE", default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=1))]
+    index_base: u8,
+
+    /// Follow durations and byte sizes, such as 30s or 512Mi, with their value
+    /// in seconds or bytes
+    #[arg(long)]
+    annotate_units: bool,
+
+    /// Wrap environment variable interpolations, such as ${VAR} and $VAR, in backticks
+    #[arg(long)]
+    highlight_vars: bool,
+
+    /// Remove ANSI escape sequences, such as terminal colors, from string values
+    #[arg(long)]
+    strip_ansi: bool,
+
+    /// Wrap descriptions onto a new line before they grow wider than N characters,
+    /// breaking only between words
+    #[arg(long, value_name = "N")]
+    wrap_width: Option<usize>,
+
+    /// Truncate multi-line values to at most this many lines within a table cell
+    #[arg(long, value_name = "N")]
+    max_cell_lines: Option<usize>,
+
+    /// Include a column stating whether each value must be provided
+    #[arg(long)]
+    show_required: bool,
+
+    /// A file listing the dotted paths of values that must be provided, one per
+    /// line, in addition to any annotated with @required
+    #[arg(long, value_name = "FILE")]
+    required_from: Option<String>,
+
+    /// Give each row an HTML anchor derived from its name, so it can be linked to
+    #[arg(long)]
+    anchors: bool,
+
+    /// Only document keys down to this level, summarizing any deeper values inline,
+    /// e.g. 1 documents top-level keys only
+    #[arg(long, value_name = "N", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    levels: Option<usize>,
+
+    /// Write a map holding a single scalar entry as 'key: value' on its parent's row
+    #[arg(long)]
+    inline_single_maps: bool,
+
+    /// Describe rows within a list that have no comment of their own with the comment of their list
+    #[arg(long)]
+    inherit_list_descriptions: bool,
+
+    /// Prefix each row with a 1-based row number
+    #[arg(long)]
+    number_rows: bool,
+
+    /// How empty maps and lists are represented within the table
+    #[arg(long, value_enum, value_name = "MODE", default_value_t)]
+    empty_container: EmptyContainer,
+
+    /// The order in which rows appear within the table
+    #[arg(long, value_enum, value_name = "ORDER", default_value_t)]
+    sort: SortOrder,
+
+    /// Follow the generated document with a summary report, written in markdown
+    #[arg(long, value_enum, value_name = "REPORT")]
+    report: Option<Report>,
+
+    /// Write a one-line summary of the values documented to stderr, for CI logs
+    #[arg(long)]
+    summary_line: bool,
+
+    /// Report questionable constructs within the document as warnings
+    #[arg(long)]
+    lint: bool,
+
+    /// Validate the document against a JSON schema, reporting any violations
+    #[arg(long, value_name = "SCHEMA")]
+    validate_against: Option<String>,
+
+    /// Fail instead of generating documentation when schema validation fails
+    #[arg(long, requires = "validate_against")]
+    strict: bool,
+
+    /// Check the document, reporting any warnings along with a summary line, without
+    /// generating any output
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Exit with an error when any warning was reported, such as from --lint
+    #[arg(long)]
+    fail_on_warnings: bool,
+
+    /// Suppress warnings and informational messages written to stderr
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// When to color output
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t)]
+    color: ColorChoice,
+
     /// Print build time version information
     #[arg(short = 'V', long)]
     version: bool,
 }
 
+/// Routes informational output to stderr, unless silenced by --quiet
+struct Logger {
+    quiet: bool,
+    color: bool,
+    /// Every warning is counted, including those silenced by --quiet
+    warnings: Cell<usize>,
+}
+
+impl Logger {
+    fn warn(&self, message: impl Display) {
+        self.warnings.set(self.warnings.get() + 1);
+        if self.quiet {
+            return;
+        }
+
+        if self.color {
+            eprintln!("\x1b[33mwarning\x1b[0m: {message}");
+        } else {
+            eprintln!("warning: {message}");
+        }
+    }
+}
+
 fn main() -> Result<()> {
     let args = Args::parse();
+    let logger = Logger {
+        quiet: args.quiet,
+        color: args.color.enabled(&io::stderr()),
+        warnings: Cell::new(0),
+    };
 
     if args.version {
         print_version_info();
@@ -47,24 +381,310 @@ fn main() -> Result<()> {
 
     let file = args.file.context("FILE argument is required")?;
 
-    let mut content = String::new();
+    let mut bytes = Vec::new();
     if file == "-" {
         io::stdin()
-            .read_to_string(&mut content)
+            .read_to_end(&mut bytes)
             .context("Failed to read from stdin")?;
     } else {
-        content =
-            fs::read_to_string(&file).with_context(|| format!("failed to read file: {}", file))?;
+        bytes = fs::read(&file).with_context(|| format!("failed to read file: {}", file))?;
     }
 
-    let document = parse(&content)?;
-    if let Some(doc) = document {
-        let markdown = render_markdown(&doc)?;
-        println!("{}", markdown);
+    let input_format = args
+        .input_format
+        .unwrap_or_else(|| InputFormat::from_path(&file));
+    let content = decode_input_with_encoding(bytes, input_format, args.encoding)
+        .with_context(|| format!("failed to decode input: {}", file))?;
+
+    let parse_options = ParseOptions {
+        comment_style: args.comment_style,
+        comment_marker: args.comment_marker,
+        keep_comment_only: args.keep_comment_only,
+        preserve_comment_breaks: args.preserve_comment_breaks,
+        timestamps: !args.no_timestamps,
+        merge_comment_groups: args.merge_comment_groups,
+        strict_anchors: args.strict_anchors,
+    };
+
+    let redact_patterns = compile_patterns(&args.redact)?;
+
+    let template = match &args.template {
+        Some(template_file) => Some(
+            fs::read_to_string(template_file)
+                .with_context(|| format!("failed to read template: {}", template_file))?,
+        ),
+        None => env::var("YAM_TEMPLATE").ok(),
+    };
+
+    let labels = match &args.locale {
+        Some(locale_file) => {
+            let locale = fs::read_to_string(locale_file)
+                .with_context(|| format!("failed to read locale: {}", locale_file))?;
+            Labels::from_yaml(&locale)
+                .with_context(|| format!("failed to load locale: {}", locale_file))?
+        }
+        None => Labels::default(),
+    };
+
+    let required_paths = match &args.required_from {
+        Some(required_file) => read_required_paths(required_file)?,
+        None => Vec::new(),
+    };
+
+    let options = RenderOptions {
+        number_rows: args.number_rows,
+        empty_container: args.empty_container,
+        sort: args.sort,
+        layout: args.layout,
+        index_style: args.index_style,
+        index_base: usize::from(args.index_base),
+        annotate_units: args.annotate_units,
+        max_cell_lines: args.max_cell_lines,
+        highlight_vars: args.highlight_vars,
+        strip_ansi: args.strip_ansi,
+        anchors: args.anchors,
+        levels: args.levels,
+        inline_single_maps: args.inline_single_maps,
+        inherit_list_descriptions: args.inherit_list_descriptions,
+        ascii: args.ascii,
+        show_required: args.show_required,
+        required_paths,
+        max_rows: args.max_rows,
+        show_definitions: args.show_definitions,
+        list_tables: args.flatten_lists_of_maps_as_columns,
+        template,
+        code_keys: args.code_keys,
+        labels,
+        wrap_width: args.wrap_width,
+        hide_marker: args.hide_internal.then_some(args.internal_marker),
+    };
+
+    let documents = parse_all_with_options(&content, &parse_options)?;
+    let document_count = documents.len();
+    let multi_document = document_count > 1;
+    let markdown = matches!(args.format, Format::Markdown | Format::MarkdownTableOnly);
+    if args.flatten_lists_of_maps_as_columns && !markdown {
+        bail!("--flatten-lists-of-maps-as-columns is only supported by the markdown format");
+    }
+    if args.template.is_some() && !markdown {
+        bail!("--template is only supported by the markdown format");
+    }
+    if args.layout == Layout::List && !markdown {
+        bail!("--layout list is only supported by the markdown format");
+    }
+    // JSON rows have nowhere to note how many rows were left out
+    if args.emit_rows_json && args.max_rows.is_some() {
+        bail!("--max-rows is not supported with --emit-rows-json");
+    }
+    if args.report.is_some()
+        && !matches!(
+            args.format,
+            Format::Markdown | Format::MarkdownMinimal | Format::MarkdownTableOnly
+        )
+    {
+        bail!("--report is only supported by the markdown formats");
+    }
+    if args.locale.is_some()
+        && matches!(args.format, Format::Xml | Format::Properties | Format::Yaml)
+    {
+        bail!("--locale is not supported by the xml, properties and yaml formats");
+    }
+
+    let stream = args.stream || content.len() > STREAM_THRESHOLD;
+    let mut summary = Summary::default();
+    let mut json_rows = Vec::new();
+
+    // A title would leave the JSON rows unreadable
+    if let Some(text) = &args.title
+        && !args.dry_run
+        && !args.emit_rows_json
+    {
+        println!("{}", title(args.format, text));
+    }
+
+    for (index, mut doc) in documents.into_iter().enumerate() {
+        if args.explode_keys {
+            doc = explode_keys(&doc)?;
+        }
+
+        if args.sort_maps {
+            doc.sort_maps();
+        }
+
+        if let Some(entry) = &args.entry {
+            let root = doc
+                .root
+                .get_path_with_base(entry, usize::from(args.index_base))
+                .with_context(|| format!("entry path not found: {}", entry))?
+                .clone();
+            doc = Document {
+                root,
+                definitions: doc.definitions,
+            };
+        }
+
+        if args.lint {
+            for lint in lint_document(&doc) {
+                logger.warn(lint);
+            }
+        }
+
+        if let Some(schema_file) = &args.validate_against {
+            let schema = fs::read_to_string(schema_file)
+                .with_context(|| format!("failed to read schema: {}", schema_file))?;
+            let schema = serde_json::from_str(&schema)
+                .with_context(|| format!("failed to parse schema: {}", schema_file))?;
+
+            let violations = validate_document(&doc, &schema)?;
+            for violation in &violations {
+                logger.warn(violation);
+            }
+
+            if args.strict && !violations.is_empty() {
+                bail!(
+                    "document failed schema validation with {} violation(s)",
+                    violations.len()
+                );
+            }
+        }
+
+        summary.add(&doc);
+        if args.dry_run {
+            continue;
+        }
+
+        // Pruned here for the formats that write the document rather than its rows
+        if let Some(marker) = &options.hide_marker {
+            doc.remove_marked(marker);
+        }
+
+        // Tallied ahead of redaction, which replaces values with strings
+        let type_report = args
+            .report
+            .map(|Report::Types| render_type_report(&count_types(&doc)));
+
+        if !redact_patterns.is_empty() {
+            doc = redact_document(&doc, &redact_patterns, &options);
+        }
+
+        if args.emit_rows_json {
+            json_rows.push(table_rows(&doc, &options));
+            continue;
+        }
+
+        // Only the very end of the output drops its newline
+        let end = if args.no_trailing_newline && index + 1 == document_count {
+            ""
+        } else {
+            "\n"
+        };
+        let body_end = if type_report.is_some() { "\n" } else { end };
+
+        if multi_document {
+            if index > 0
+                && let Some(rule) = document_separator(args.format)
+            {
+                println!("{}", rule);
+            }
+            println!("{}", heading(args.format, &doc, index + 1));
+        }
+        if stream && matches!(args.format, Format::Markdown) {
+            let mut stdout = BufWriter::new(io::stdout().lock());
+            render_markdown_stream(&doc, &options, &mut stdout)?;
+            write!(stdout, "{}", body_end)?;
+            stdout.flush()?;
+        } else {
+            print!("{}{}", render(args.format, &doc, &options)?, body_end);
+        }
+
+        if let Some(report) = type_report {
+            print!("{}{}", report, end);
+        }
+    }
+
+    if args.emit_rows_json && !args.dry_run {
+        let json = match json_rows.as_slice() {
+            [rows] => serde_json::to_string_pretty(rows)?,
+            _ => serde_json::to_string_pretty(&json_rows)?,
+        };
+        println!("{}", json);
+    }
+
+    if args.summary_line || args.dry_run {
+        eprintln!("{}", summary);
+    }
+    if args.fail_on_warnings && logger.warnings.get() > 0 {
+        bail!("{} warning(s) reported", logger.warnings.get());
     }
     Ok(())
 }
 
+fn render(format: Format, doc: &Document<'_>, options: &RenderOptions) -> Result<String, YamError> {
+    match format {
+        Format::Markdown => render_markdown(doc, options),
+        Format::MarkdownMinimal => render_markdown_minimal(doc, options),
+        Format::MarkdownTableOnly => {
+            render_markdown(doc, options).map(|markdown| markdown.trim().to_string())
+        }
+        Format::Confluence => render_confluence(doc, options),
+        Format::Grid => render_grid(doc, options),
+        Format::Slack => render_slack(doc, options),
+        Format::Xml => render_xml(doc),
+        Format::Properties => render_properties(doc, options),
+        Format::Yaml => render_yaml(doc),
+    }
+}
+
+fn title(format: Format, text: &str) -> String {
+    match format {
+        Format::Markdown | Format::MarkdownMinimal | Format::MarkdownTableOnly => {
+            format!("**{}**", text)
+        }
+        Format::Confluence | Format::Slack => format!("*{}*", text),
+        Format::Grid => text.to_string(),
+        Format::Xml => format!("<!-- {} -->", text),
+        Format::Properties | Format::Yaml => format!("# {}", text),
+    }
+}
+
+fn heading(format: Format, doc: &Document<'_>, number: usize) -> String {
+    let title = document_heading(doc, number);
+    match format {
+        Format::Markdown | Format::MarkdownMinimal | Format::MarkdownTableOnly => {
+            format!("## {}", title)
+        }
+        Format::Confluence => format!("h2. {}", title),
+        Format::Grid => title,
+        Format::Slack => format!("*{}*", title),
+        Format::Xml => format!("<!-- {} -->", title),
+        Format::Properties => format!("# {}", title),
+        Format::Yaml => format!("--- # {}", title),
+    }
+}
+
+// A markdown rule between documents, which must be set apart from a preceding table
+// by a blank line that the table only format leaves out
+fn document_separator(format: Format) -> Option<&'static str> {
+    match format {
+        Format::Markdown | Format::MarkdownMinimal => Some("---\n"),
+        Format::MarkdownTableOnly => Some("\n---\n"),
+        _ => None,
+    }
+}
+
+// Blank lines and lines starting with a `#` are ignored
+fn read_required_paths(file: &str) -> Result<Vec<String>> {
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("failed to read required paths: {}", file))?;
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
 fn print_version_info() {
     println!("version:    {}", built_info::PKG_VERSION);
     println!("rustc:      {}", built_info::RUSTC_VERSION);
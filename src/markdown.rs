@@ -1,71 +1,1257 @@
-use crate::parser::{Document, Scalar, ScalarType};
-use anyhow::{Context, Result};
+use crate::error::{Result, YamError};
+use crate::{
+    locale::Labels,
+    parser::{Document, MapItem, Scalar, ScalarType, parse},
+    units::describe_units,
+};
+use regex::Regex;
 use serde::Serialize;
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    sync::LazyLock,
+};
 
 pub const TEMPLATE: &str = r#"
-| Name | Value | Description |
-|------|-------|-------------|
+|{% if number_rows %} # |{% endif %} {{ labels.name }} | {{ labels.value }} |{% if show_required %} {{ labels.required }} |{% endif %} {{ labels.description }} |
+|{% if number_rows %}---|{% endif %}------|-------|{% if show_required %}----------|{% endif %}-------------|
 {%- for row in rows %}
-| {{ row.name }} | {{ row.value }} | {{ row.description }} |
+|{% if number_rows %} {{ row.index }} |{% endif %} {% if anchors %}<a id="{{ row.anchor }}"></a>{% endif %}{{ row.name }} | {{ row.value }} |{% if show_required %} {% if row.required %}{{ labels.yes }}{% else %}{{ labels.no }}{% endif %} |{% endif %} {{ row.description }} |
 {%- endfor %}
 "#;
 
+pub const MINIMAL_TEMPLATE: &str = r#"
+|{% if number_rows %} # |{% endif %} {{ labels.name }} | {{ labels.value }} |
+|{% if number_rows %}---|{% endif %}------|-------|
+{%- for row in rows %}
+|{% if number_rows %} {{ row.index }} |{% endif %} {{ row.name }} | {{ row.value }} |
+{%- endfor %}
+"#;
+
+#[derive(Debug, Default, Clone)]
+pub struct RenderOptions {
+    /// Prefix each row with a 1-based row number
+    pub number_rows: bool,
+    /// How empty maps and lists are represented within the table
+    pub empty_container: EmptyContainer,
+    /// The order in which rows appear within the table
+    pub sort: SortOrder,
+    /// How the document is laid out
+    pub layout: Layout,
+    /// How list indices are written within a row name
+    pub index_style: IndexStyle,
+    /// The index given to the first item of a list, 0 or 1, when naming its rows
+    pub index_base: usize,
+    /// Follow durations and byte sizes with their value in seconds or bytes
+    pub annotate_units: bool,
+    /// The most lines of a multi-line value shown within a table cell
+    pub max_cell_lines: Option<usize>,
+    /// Wrap environment variable interpolations, such as `${VAR}`, in backticks
+    pub highlight_vars: bool,
+    /// Remove ANSI escape sequences, such as terminal colors, from string values
+    pub strip_ansi: bool,
+    /// Give each row an HTML anchor derived from its name, so it can be linked to
+    pub anchors: bool,
+    /// Only document keys down to this level, summarizing any deeper values inline
+    pub levels: Option<usize>,
+    /// Write a map holding a single scalar entry as `key: value` on its parent's row
+    pub inline_single_maps: bool,
+    /// Describe each row within a list, including those of a list of maps, without
+    /// a comment of its own using the comment of its list
+    pub inherit_list_descriptions: bool,
+    /// Draw grid borders with plain ASCII rather than box-drawing characters
+    pub ascii: bool,
+    /// Include a column stating whether each value must be provided
+    pub show_required: bool,
+    /// Dotted paths of values that must be provided, alongside those annotated
+    /// with `@required`
+    pub required_paths: Vec<String>,
+    /// Stop adding rows to the table once it holds this many, noting how many
+    /// were left out
+    pub max_rows: Option<usize>,
+    /// Precede the table with one listing each `&anchor` and the value it defines
+    pub show_definitions: bool,
+    /// Give a nested list of maps sharing the same keys its own table, with a
+    /// column per key, rather than a row per value
+    pub list_tables: bool,
+    /// A Tera template used in place of [`TEMPLATE`] to render the table
+    pub template: Option<String>,
+    /// Write each name within a markdown table as inline code
+    pub code_keys: bool,
+    /// The text of table headers, along with booleans and nulls written as words
+    pub labels: Labels,
+    /// Break descriptions onto a new line before they grow wider than this many
+    /// characters
+    pub wrap_width: Option<usize>,
+    /// Leave out any value whose comment contains this marker, such as `@internal`,
+    /// along with everything nested within it
+    pub hide_marker: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum IndexStyle {
+    /// Separate list indices with a dot, e.g. `ports.0`
+    #[default]
+    Dot,
+    /// Wrap list indices in brackets, e.g. `ports[0]`
+    Bracket,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Layout {
+    /// A table with a row per value
+    #[default]
+    Table,
+    /// A nested bullet list following the structure of the document
+    List,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum EmptyContainer {
+    /// Leave empty maps and lists out of the table
+    Omit,
+    /// Render empty maps and lists as `{}` and `[]`
+    #[default]
+    Brackets,
+    /// Render empty maps and lists as `null`
+    Null,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SortOrder {
+    /// Keep rows in the order they appear within the document
+    #[default]
+    None,
+    /// Sort rows lexicographically by their name
+    Keys,
+    /// Sort rows lexicographically by their rendered value
+    Values,
+    /// Group rows by the type of their value
+    Types,
+}
+
+impl SortOrder {
+    fn sort(&self, rows: &mut [TableRow]) {
+        match self {
+            SortOrder::None => {}
+            SortOrder::Keys => rows.sort_by(|a, b| a.name.cmp(&b.name)),
+            SortOrder::Values => rows.sort_by(|a, b| a.value.cmp(&b.value)),
+            SortOrder::Types => rows.sort_by(|a, b| a.value_type.cmp(&b.value_type)),
+        }
+    }
+}
+
+/// A single row of the rendered table, describing one value within the document
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TableRow {
+    /// The 1-based position of the row within the table
+    pub index: usize,
+    /// The dotted path to the value, `ingress.tls.0`
+    pub name: String,
+    pub value: String,
+    pub description: String,
+    pub value_type: String,
+    pub required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
+    /// The anchor named by the `*alias` the value was resolved from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+}
+
+/// A table row without a description, for documents that carry no comments
 #[derive(Debug, Clone, Serialize)]
-struct TableRow {
+struct MinimalTableRow {
+    index: usize,
     name: String,
     value: String,
-    description: String,
 }
 
-pub fn render_markdown(document: &Document<'_>) -> Result<String> {
+impl From<TableRow> for MinimalTableRow {
+    fn from(row: TableRow) -> Self {
+        Self {
+            index: row.index,
+            name: row.name,
+            value: row.value,
+        }
+    }
+}
+
+/// Parses the text and renders it as a markdown table in a single call, returning
+/// `None` when there is no document to render
+pub fn document_markdown(text: &str, options: &RenderOptions) -> Result<Option<String>> {
+    parse(text)?
+        .map(|document| render_markdown(&document, options))
+        .transpose()
+}
+
+pub fn render_markdown(document: &Document<'_>, options: &RenderOptions) -> Result<String> {
+    if options.layout == Layout::List {
+        return Ok(render_markdown_list(document, options));
+    }
+    render_markdown_with_hook(document, options, |_| {})
+}
+
+/// Renders the markdown table as [`render_markdown`] does, but first hands the
+/// flattened rows to the hook, which is free to add, remove, reorder or rewrite
+/// them. Rows are numbered and given any missing anchor afterwards, so those the
+/// hook adds need neither. The list layout writes no table rows, so it is rejected
+pub fn render_markdown_with_hook<F>(
+    document: &Document<'_>,
+    options: &RenderOptions,
+    mut hook: F,
+) -> Result<String>
+where
+    F: FnMut(&mut Vec<TableRow>),
+{
+    if options.layout == Layout::List {
+        return Err(YamError::ListLayoutHook);
+    }
+
+    let Flattened { mut rows, omitted } = flatten_document(document, options);
+    hook(&mut rows);
+    for (index, row) in rows.iter_mut().enumerate() {
+        row.index = index + 1;
+    }
+    if options.anchors {
+        fill_anchors(&mut rows);
+    }
+    for row in &mut rows {
+        prepare_cells(row, options);
+    }
+
+    let mut output = render_description(document);
+    output.push_str(&render_definitions(document, options));
+    let template = options.template.as_deref().unwrap_or(TEMPLATE);
+    output.push_str(&render_template(template, &rows, options)?);
+    push_truncation_note(&mut output, omitted);
+    output.push_str(&render_list_tables(document, options));
+    Ok(output)
+}
+
+/// Writes a table, headed by its path, for each list left out of the main table by
+/// `list_tables`
+fn render_list_tables(document: &Document<'_>, options: &RenderOptions) -> String {
+    let mut output = String::new();
+    if options.list_tables {
+        push_list_tables(&document.root, String::new(), options, &mut output);
+    }
+    output
+}
+
+fn push_list_tables(
+    scalar: &Scalar<'_>,
+    key: String,
+    options: &RenderOptions,
+    output: &mut String,
+) {
+    if !key.is_empty() && is_hidden(scalar, options) {
+        return;
+    }
+
+    match &scalar.value {
+        ScalarType::List(list) if !key.is_empty() && is_uniform_map_list(list) => {
+            let ScalarType::Map(first) = &list[0].value else {
+                return;
+            };
+            let columns: Vec<&str> = first.iter().map(|entry| entry.key).collect();
+
+            output.push_str(&format!("\n### {}\n\n", key));
+            output.push_str(&format!("| {} |\n", columns.join(" | ")));
+            let rules: Vec<String> = columns.iter().map(|c| "-".repeat(c.len() + 2)).collect();
+            output.push_str(&format!("|{}|\n", rules.join("|")));
+
+            for item in list {
+                let ScalarType::Map(map) = &item.value else {
+                    continue;
+                };
+                let cells: Vec<String> = map
+                    .iter()
+                    .map(|entry| {
+                        let value = entry
+                            .value
+                            .explicit_default
+                            .clone()
+                            .unwrap_or_else(|| render_value(&entry.value.value, options));
+                        markdown_cell(&value, options)
+                    })
+                    .collect();
+                output.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+        }
+        ScalarType::Map(map) => {
+            for entry in map {
+                let new_key = if key.is_empty() {
+                    entry.key.to_string()
+                } else {
+                    format!("{}.{}", key, entry.key)
+                };
+                push_list_tables(&entry.value, new_key, options, output);
+            }
+        }
+        ScalarType::List(list) => {
+            for (index, item) in list.iter().enumerate() {
+                let new_key = list_item_key(&key, index, options);
+                push_list_tables(item, new_key, options, output);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Every item is a map of scalars with the same keys in the same order, so each
+// key can become a column
+fn is_uniform_map_list<'a>(list: &[Scalar<'a>]) -> bool {
+    let keys = |item: &Scalar<'a>| match &item.value {
+        ScalarType::Map(map)
+            if !map.is_empty()
+                && map.iter().all(|entry| {
+                    !matches!(entry.value.value, ScalarType::Map(_) | ScalarType::List(_))
+                }) =>
+        {
+            Some(map.iter().map(|entry| entry.key).collect::<Vec<_>>())
+        }
+        _ => None,
+    };
+
+    match list.first().and_then(keys) {
+        Some(first) => list.iter().all(|item| keys(item).as_ref() == Some(&first)),
+        None => false,
+    }
+}
+
+// A comment trailing a root collection, `{x: 1} # note`, describes the whole
+// document and is written as a paragraph above the table. A comment above a block
+// document also reaches the root, but it belongs to the first entry and stays there
+fn render_description(document: &Document<'_>) -> String {
+    let root = &document.root;
+    let first_comment = match &root.value {
+        ScalarType::Map(map) => map.first().map(|entry| &entry.value.comment),
+        ScalarType::List(list) => list.first().map(|item| &item.comment),
+        _ => return String::new(),
+    };
+
+    match &root.comment {
+        Some(comment) if first_comment != Some(&root.comment) => format!("\n{}\n", comment),
+        _ => String::new(),
+    }
+}
+
+// Each anchored value is written on a single row, with maps and lists summarized
+// using flow syntax
+fn render_definitions(document: &Document<'_>, options: &RenderOptions) -> String {
+    if !options.show_definitions || document.definitions.is_empty() {
+        return String::new();
+    }
+
+    let labels = &options.labels;
+    let mut output = format!(
+        "\n### Definitions\n\n| {} | {} | {} |\n|--------|-------|-------------|\n",
+        labels.anchor, labels.value, labels.description
+    );
+    for definition in &document.definitions {
+        let scalar = &definition.value;
+        let value = scalar
+            .explicit_default
+            .clone()
+            .unwrap_or_else(|| match &scalar.value {
+                ScalarType::Map(_) | ScalarType::List(_) => inline_summary(&scalar.value),
+                leaf => render_value(leaf, options),
+            });
+        let name = if options.anchors {
+            format!(
+                "<a id=\"{}\"></a>{}",
+                definition_anchor(definition.name),
+                definition.name
+            )
+        } else {
+            definition.name.to_string()
+        };
+        output.push_str(&format!(
+            "| {} | {} | {} |\n",
+            name,
+            markdown_cell(&value, options),
+            scalar
+                .comment
+                .as_deref()
+                .unwrap_or_default()
+                .replace('\n', "<br>")
+        ));
+    }
+    output
+}
+
+/// Writes the markdown table row by row while walking the document, without first
+/// collecting every row. Output matches [`render_markdown`], which is used instead
+/// whenever the rows must be sorted, laid out as a list or rendered by a custom template
+pub fn render_markdown_stream<W: Write>(
+    document: &Document<'_>,
+    options: &RenderOptions,
+    writer: &mut W,
+) -> Result<()> {
+    if options.layout == Layout::List
+        || !matches!(options.sort, SortOrder::None)
+        || options.template.is_some()
+    {
+        writer.write_all(render_markdown(document, options)?.as_bytes())?;
+        return Ok(());
+    }
+
+    writer.write_all(render_description(document).as_bytes())?;
+    writer.write_all(render_definitions(document, options).as_bytes())?;
+    write_table_header(writer, options)?;
+
+    let mut anchors = Anchors::default();
+    let mut index = 0;
+    let mut result = Ok(());
+    let write_row = |mut row: TableRow| {
+        if result.is_err() {
+            return;
+        }
+        index += 1;
+        row.index = index;
+        if options.anchors {
+            row.anchor = Some(anchors.next(&row.name));
+        }
+        prepare_cells(&mut row, options);
+        result = write_table_row(writer, &row, options);
+    };
+    let mut limited = Limited::new(write_row, options.max_rows);
+    flatten_scalar(
+        &document.root,
+        String::new(),
+        0,
+        None,
+        options,
+        &mut limited,
+    );
+    let omitted = limited.omitted;
+    result?;
+
+    writeln!(writer)?;
+    if omitted > 0 {
+        writeln!(writer, "\n{}", truncation_note(omitted))?;
+    }
+    writer.write_all(render_list_tables(document, options).as_bytes())?;
+    Ok(())
+}
+
+// Both of these mirror TEMPLATE, so the streamed table is byte for byte identical
+fn write_table_header<W: Write>(writer: &mut W, options: &RenderOptions) -> io::Result<()> {
+    let (number, number_rule) = if options.number_rows {
+        (" # |", "---|")
+    } else {
+        ("", "")
+    };
+    let (required, required_rule) = if options.show_required {
+        (format!(" {} |", options.labels.required), "----------|")
+    } else {
+        (String::new(), "")
+    };
+
+    writeln!(writer)?;
+    let labels = &options.labels;
+    writeln!(
+        writer,
+        "|{number} {} | {} |{required} {} |",
+        labels.name, labels.value, labels.description
+    )?;
+    write!(
+        writer,
+        "|{number_rule}------|-------|{required_rule}-------------|"
+    )
+}
+
+fn write_table_row<W: Write>(
+    writer: &mut W,
+    row: &TableRow,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    write!(writer, "\n|")?;
+    if options.number_rows {
+        write!(writer, " {} |", row.index)?;
+    }
+    write!(writer, " ")?;
+    if let Some(anchor) = &row.anchor {
+        write!(writer, "<a id=\"{}\"></a>", anchor)?;
+    }
+    write!(writer, "{} | {} |", row.name, row.value)?;
+    if options.show_required {
+        write!(writer, " {} |", options.labels.required_text(row.required))?;
+    }
+    write!(writer, " {} |", row.description)
+}
+
+// Line breaks would end the table row, so they become <br> within a cell. Anchors
+// are derived from the name beforehand, so wrapping it in code never changes them
+fn prepare_cells(row: &mut TableRow, options: &RenderOptions) {
+    row.value = markdown_cell(&row.value, options);
+    if let Some(alias) = &row.alias {
+        row.value = format!("{} [↑](#{})", row.value, definition_anchor(alias));
+    }
+    row.description = row.description.replace('\n', "<br>");
+    if options.code_keys {
+        row.name = code_span(&row.name);
+    }
+}
+
+// The fence is one backtick longer than any run within the text, and is padded with
+// a space when the text starts or ends with a backtick. A pipe would still end the
+// cell within code, so it is escaped
+fn code_span(text: &str) -> String {
+    let longest_run = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest_run + 1);
+    let padding = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!(
+        "{fence}{padding}{}{padding}{fence}",
+        text.replace('|', "\\|")
+    )
+}
+
+/// Renders a markdown table with only the Name and Value columns
+pub fn render_markdown_minimal(document: &Document<'_>, options: &RenderOptions) -> Result<String> {
+    let Flattened { rows, omitted } = flatten_document(document, options);
+    let rows: Vec<MinimalTableRow> = rows
+        .into_iter()
+        .map(|mut row| {
+            row.value = markdown_cell(&row.value, options);
+            if options.code_keys {
+                row.name = code_span(&row.name);
+            }
+            MinimalTableRow::from(row)
+        })
+        .collect();
+
+    let mut output = render_template(MINIMAL_TEMPLATE, &rows, options)?;
+    push_truncation_note(&mut output, omitted);
+    Ok(output)
+}
+
+/// Notes how many rows were left out of a table limited by `max_rows`
+pub(crate) fn truncation_note(omitted: usize) -> String {
+    let suffix = if omitted == 1 { "row" } else { "rows" };
+    format!("(truncated, {} {} omitted)", omitted, suffix)
+}
+
+// Written as its own paragraph, so it is never mistaken for a row of the table
+pub(crate) fn push_truncation_note(output: &mut String, omitted: usize) {
+    if omitted > 0 {
+        output.push_str(&format!("\n{}\n", truncation_note(omitted)));
+    }
+}
+
+/// Truncates a multi-line value to `max_cell_lines` when set, so it can sit within
+/// a table cell. Each line keeps its indentation, while the line break a clipped
+/// block scalar ends with is dropped, as it would only leave an empty last line
+pub(crate) fn collapse_cell(value: &str, options: &RenderOptions) -> String {
+    let value = value.strip_suffix('\n').unwrap_or(value);
+    let Some(max) = options.max_cell_lines else {
+        return value.to_string();
+    };
+
+    let lines: Vec<&str> = value.lines().collect();
+    if lines.len() <= max {
+        return value.to_string();
+    }
+
+    let remaining = lines.len() - max;
+    let suffix = if remaining == 1 { "line" } else { "lines" };
+    format!(
+        "{}\n… ({} more {})",
+        lines[..max].join("\n"),
+        remaining,
+        suffix
+    )
+}
+
+// Markdown collapses whitespace, so the indentation of each line is kept with
+// non-breaking spaces, and line breaks become <br> so they don't end the row
+fn markdown_cell(value: &str, options: &RenderOptions) -> String {
+    let collapsed = collapse_cell(value, options);
+    if !collapsed.contains('\n') {
+        return collapsed;
+    }
+
+    collapsed
+        .split('\n')
+        .map(|line| {
+            let content = line.trim_start_matches(' ');
+            "&nbsp;".repeat(line.len() - content.len()) + content
+        })
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+/// Heads a document within a multi-document stream, naming Kubernetes-style resources
+/// after their kind and name, otherwise falling back to the 1-based document number
+pub fn document_heading(document: &Document<'_>, number: usize) -> String {
+    document
+        .resource_name()
+        .unwrap_or_else(|| format!("Document {}", number))
+}
+
+/// Serializes the flattened rows, as handed to the table template, into JSON
+/// for rendering with an external templating toolchain. The array has no room for
+/// a truncation note, so rows past `max_rows` are left out without one
+pub fn render_rows_json(document: &Document<'_>, options: &RenderOptions) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&table_rows(
+        document, options,
+    ))?)
+}
+
+/// The flattened rows of the document, as handed to the table template
+pub fn table_rows(document: &Document<'_>, options: &RenderOptions) -> Vec<TableRow> {
+    flatten_document(document, options).rows
+}
+
+fn render_template<R: Serialize>(
+    template: &str,
+    rows: &[R],
+    options: &RenderOptions,
+) -> Result<String> {
     let mut tera = tera::Tera::default();
-    tera.add_raw_template("main", TEMPLATE)
-        .context("failed to parse template")?;
+    tera.add_raw_template("main", template)
+        .map_err(YamError::Template)?;
 
     let mut context = tera::Context::new();
-    context.insert("rows", &flatten_document(document));
+    context.insert("rows", rows);
+    context.insert("number_rows", &options.number_rows);
+    context.insert("show_required", &options.show_required);
+    context.insert("anchors", &options.anchors);
+    context.insert("labels", &options.labels);
 
-    tera.render("main", &context)
-        .context("failed to render template")
+    tera.render("main", &context).map_err(YamError::Render)
 }
 
-fn flatten_document(document: &Document<'_>) -> Vec<TableRow> {
+/// Flattens the document into a row per value. Rows depend only on the structure of
+/// the document and never on how it was written, so `[80, 443]` and the equivalent
+/// block sequence produce identical rows
+pub(crate) fn flatten_document(document: &Document<'_>, options: &RenderOptions) -> Flattened {
+    // Sorted rows can only be limited once every row is known, otherwise rows past
+    // the limit are counted without being built
+    if options.sort != SortOrder::None {
+        let mut rows = Vec::new();
+        flatten_scalar(
+            &document.root,
+            String::new(),
+            0,
+            None,
+            options,
+            &mut |row| rows.push(row),
+        );
+        if options.anchors {
+            assign_anchors(&mut rows);
+        }
+        options.sort.sort(&mut rows);
+
+        let omitted = match options.max_rows {
+            Some(max) if rows.len() > max => rows.len() - max,
+            _ => 0,
+        };
+        rows.truncate(rows.len() - omitted);
+        return numbered(rows, omitted);
+    }
+
     let mut rows = Vec::new();
-    flatten_scalar(&document.root, String::new(), &mut rows);
-    rows
+    let mut limited = Limited::new(|row| rows.push(row), options.max_rows);
+    flatten_scalar(
+        &document.root,
+        String::new(),
+        0,
+        None,
+        options,
+        &mut limited,
+    );
+    let omitted = limited.omitted;
+    if options.anchors {
+        assign_anchors(&mut rows);
+    }
+    numbered(rows, omitted)
+}
+
+fn numbered(mut rows: Vec<TableRow>, omitted: usize) -> Flattened {
+    for (index, row) in rows.iter_mut().enumerate() {
+        row.index = index + 1;
+    }
+    Flattened { rows, omitted }
+}
+
+/// Receives each row of a document as it is flattened
+trait RowSink {
+    fn push(&mut self, row: TableRow);
+
+    /// Once full, any further row is only counted through [`RowSink::skip`], saving
+    /// the work of building it
+    fn is_full(&self) -> bool {
+        false
+    }
+
+    fn skip(&mut self) {}
+}
+
+impl<F: FnMut(TableRow)> RowSink for F {
+    fn push(&mut self, row: TableRow) {
+        self(row)
+    }
+}
+
+/// Hands on at most `max_rows` rows, counting those left out
+struct Limited<F> {
+    emit: F,
+    remaining: Option<usize>,
+    omitted: usize,
 }
 
-fn flatten_scalar(scalar: &Scalar<'_>, key: String, rows: &mut Vec<TableRow>) {
+impl<F: FnMut(TableRow)> Limited<F> {
+    fn new(emit: F, max_rows: Option<usize>) -> Self {
+        Self {
+            emit,
+            remaining: max_rows,
+            omitted: 0,
+        }
+    }
+}
+
+impl<F: FnMut(TableRow)> RowSink for Limited<F> {
+    fn push(&mut self, row: TableRow) {
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= 1;
+        }
+        (self.emit)(row)
+    }
+
+    fn is_full(&self) -> bool {
+        self.remaining == Some(0)
+    }
+
+    fn skip(&mut self) {
+        self.omitted += 1;
+    }
+}
+
+/// Links each row within an aliased value back to the definition of its anchor
+struct Linked<'s> {
+    sink: &'s mut dyn RowSink,
+    alias: &'s str,
+}
+
+impl RowSink for Linked<'_> {
+    fn push(&mut self, mut row: TableRow) {
+        row.alias.get_or_insert_with(|| self.alias.to_string());
+        self.sink.push(row)
+    }
+
+    fn is_full(&self) -> bool {
+        self.sink.is_full()
+    }
+
+    fn skip(&mut self) {
+        self.sink.skip()
+    }
+}
+
+/// The rows of a document, along with the number left out through `max_rows`
+pub(crate) struct Flattened {
+    pub rows: Vec<TableRow>,
+    pub omitted: usize,
+}
+
+// Anchors are assigned before sorting, so when names collide the first row within
+// the document keeps the bare slug and later rows gain an increasing suffix
+fn assign_anchors(rows: &mut [TableRow]) {
+    let mut anchors = Anchors::default();
+    for row in rows {
+        row.anchor = Some(anchors.next(&row.name));
+    }
+}
+
+// Rows added by a hook have no anchor, so they take the next one free after those
+// already given out
+fn fill_anchors(rows: &mut [TableRow]) {
+    let mut anchors = Anchors {
+        used: rows.iter().filter_map(|row| row.anchor.clone()).collect(),
+    };
+    for row in rows.iter_mut().filter(|row| row.anchor.is_none()) {
+        row.anchor = Some(anchors.next(&row.name));
+    }
+}
+
+/// Hands out a unique anchor for each row name in the order they are requested
+#[derive(Default)]
+struct Anchors {
+    used: HashSet<String>,
+}
+
+impl Anchors {
+    fn next(&mut self, name: &str) -> String {
+        let slug = slugify(name);
+        let mut anchor = slug.clone();
+        let mut suffix = 0;
+        while !self.used.insert(anchor.clone()) {
+            suffix += 1;
+            anchor = format!("{}-{}", slug, suffix);
+        }
+        anchor
+    }
+}
+
+fn definition_anchor(name: &str) -> String {
+    format!("defs-{}", slugify(name))
+}
+
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() || c == '_' {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+// Rows within an aliased value link back to the definition of its anchor, which is
+// only linkable when the definitions table is shown with anchors. A nested alias is
+// closer to the row, so it takes precedence
+fn flatten_scalar(
+    scalar: &Scalar<'_>,
+    key: String,
+    depth: usize,
+    inherited: Option<&str>,
+    options: &RenderOptions,
+    emit: &mut dyn RowSink,
+) {
+    // The root shares the comment above its first key, so it is never hidden itself
+    if depth > 0 && is_hidden(scalar, options) {
+        return;
+    }
+
+    match scalar.alias {
+        Some(alias) if options.anchors && options.show_definitions => {
+            let mut linked = Linked { sink: emit, alias };
+            flatten_value(scalar, key, depth, inherited, options, &mut linked);
+        }
+        _ => flatten_value(scalar, key, depth, inherited, options, emit),
+    }
+}
+
+fn flatten_value(
+    scalar: &Scalar<'_>,
+    key: String,
+    depth: usize,
+    inherited: Option<&str>,
+    options: &RenderOptions,
+    emit: &mut dyn RowSink,
+) {
+    let summarize = depth > 0 && options.levels.is_some_and(|levels| depth >= levels);
+
     match &scalar.value {
+        ScalarType::Map(map) if map.is_empty() => {
+            push_empty_container(scalar, key, "{}", inherited, options, emit);
+        }
+        ScalarType::List(list) if list.is_empty() => {
+            push_empty_container(scalar, key, "[]", inherited, options, emit);
+        }
+        ScalarType::Map(_) | ScalarType::List(_) if summarize => {
+            let value = || inline_summary(&scalar.value);
+            push_row(scalar, key, value, inherited, options, emit);
+        }
+        ScalarType::List(list) if depth > 0 && options.list_tables && is_uniform_map_list(list) => {
+        }
+        ScalarType::Map(map)
+            if depth > 0 && options.inline_single_maps && is_single_scalar(map) =>
+        {
+            push_inline_entry(scalar, &map[0], key, inherited, options, emit);
+        }
         ScalarType::Map(map) => {
             for entry in map {
-                let new_key = if key.is_empty() {
-                    entry.key.to_string() // Convert &str to String
+                // Rows past a full table are only counted, so they go without a name
+                let new_key = if emit.is_full() {
+                    String::new()
                 } else {
-                    format!("{}.{}", key, entry.key)
+                    map_entry_key(&key, entry.key)
                 };
-                flatten_scalar(&entry.value, new_key, rows);
+                flatten_scalar(&entry.value, new_key, depth + 1, inherited, options, emit);
             }
         }
         ScalarType::List(list) => {
+            // Every row beneath the list, however deeply nested, is described by the
+            // nearest commented list when it has no comment of its own
+            let inherited = scalar
+                .comment
+                .as_deref()
+                .filter(|_| options.inherit_list_descriptions)
+                .or(inherited);
+            for (index, item) in list.iter().enumerate() {
+                let new_key = if emit.is_full() {
+                    String::new()
+                } else {
+                    list_item_key(&key, index, options)
+                };
+                flatten_scalar(item, new_key, depth + 1, inherited, options, emit);
+            }
+        }
+        _ => push_row(
+            scalar,
+            key,
+            || render_value(&scalar.value, options),
+            inherited,
+            options,
+            emit,
+        ),
+    }
+}
+
+/// Names an entry of a map within the row names, where entries of the root map
+/// are named by their key alone
+pub(crate) fn map_entry_key(key: &str, entry: &str) -> String {
+    if key.is_empty() {
+        entry.to_string()
+    } else {
+        format!("{}.{}", key, entry)
+    }
+}
+
+// Only the displayed index is shifted by the base, items are still visited in order
+pub(crate) fn list_item_key(key: &str, index: usize, options: &RenderOptions) -> String {
+    let index = index + options.index_base;
+    match options.index_style {
+        IndexStyle::Dot => format!("{}.{}", key, index),
+        IndexStyle::Bracket => format!("{}[{}]", key, index),
+    }
+}
+
+fn is_hidden(scalar: &Scalar<'_>, options: &RenderOptions) -> bool {
+    match (&scalar.comment, &options.hide_marker) {
+        (Some(comment), Some(marker)) => comment.contains(marker.as_str()),
+        _ => false,
+    }
+}
+
+fn is_single_scalar(map: &[MapItem<'_>]) -> bool {
+    matches!(map, [entry] if !matches!(entry.value.value, ScalarType::Map(_) | ScalarType::List(_)))
+}
+
+// The entry is written as `key: value` against its parent, taking its own comment
+// in preference to that of the parent
+fn push_inline_entry(
+    scalar: &Scalar<'_>,
+    entry: &MapItem<'_>,
+    key: String,
+    inherited: Option<&str>,
+    options: &RenderOptions,
+    emit: &mut dyn RowSink,
+) {
+    if emit.is_full() {
+        emit.skip();
+        return;
+    }
+
+    let value = entry
+        .value
+        .explicit_default
+        .clone()
+        .unwrap_or_else(|| render_value(&entry.value.value, options));
+
+    let mut inlined = entry.value.clone();
+    inlined.explicit_default = None;
+    if inlined.comment.is_none() {
+        inlined.comment = scalar.comment.clone();
+    }
+    push_row(
+        &inlined,
+        key,
+        || format!("{}: {}", entry.key, value),
+        inherited,
+        options,
+        emit,
+    );
+}
+
+/// Writes a container on a single line using YAML flow syntax, `{cpu: 100m}`
+fn inline_summary(value: &ScalarType<'_>) -> String {
+    match value {
+        ScalarType::Map(map) => {
+            let entries: Vec<String> = map
+                .iter()
+                .map(|entry| format!("{}: {}", entry.key, inline_summary(&entry.value.value)))
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+        ScalarType::List(list) => {
+            let items: Vec<String> = list
+                .iter()
+                .map(|item| inline_summary(&item.value))
+                .collect();
+            format!("[{}]", items.join(", "))
+        }
+        leaf => format_scalar_value(leaf),
+    }
+}
+
+fn push_empty_container(
+    scalar: &Scalar<'_>,
+    key: String,
+    brackets: &str,
+    inherited: Option<&str>,
+    options: &RenderOptions,
+    emit: &mut dyn RowSink,
+) {
+    if let Some(value) = empty_container_value(brackets, options) {
+        push_row(scalar, key, || value, inherited, options, emit);
+    }
+}
+
+fn empty_container_value(brackets: &str, options: &RenderOptions) -> Option<String> {
+    match options.empty_container {
+        EmptyContainer::Omit => None,
+        EmptyContainer::Brackets => Some(brackets.to_string()),
+        EmptyContainer::Null => Some(options.labels.null_value.clone()),
+    }
+}
+
+// Annotated defaults and types take precedence over those detected from the document
+fn push_row(
+    scalar: &Scalar<'_>,
+    key: String,
+    value: impl FnOnce() -> String,
+    inherited: Option<&str>,
+    options: &RenderOptions,
+    emit: &mut dyn RowSink,
+) {
+    if emit.is_full() {
+        emit.skip();
+        return;
+    }
+
+    let required = scalar.required || options.required_paths.contains(&key);
+    emit.push(TableRow {
+        index: 0,
+        required,
+        anchor: None,
+        alias: None,
+        name: key,
+        value: scalar.explicit_default.clone().unwrap_or_else(value),
+        description: match (scalar.comment.as_deref().or(inherited), options.wrap_width) {
+            (Some(comment), Some(width)) => wrap_words(comment, width),
+            (comment, _) => comment.unwrap_or_default().to_string(),
+        },
+        value_type: scalar
+            .explicit_type
+            .clone()
+            .unwrap_or_else(|| scalar.value.type_name().to_string()),
+    });
+}
+
+// Lines are only broken between words, so a single word wider than the limit is
+// kept whole on a line of its own. Existing line breaks are always kept
+fn wrap_words(text: &str, width: usize) -> String {
+    let mut wrapped = String::new();
+    for (index, line) in text.split('\n').enumerate() {
+        if index > 0 {
+            wrapped.push('\n');
+        }
+
+        let mut line_width = 0;
+        for word in line.split_whitespace() {
+            let word_width = word.chars().count();
+            if line_width > 0 && line_width + 1 + word_width > width {
+                wrapped.push('\n');
+                line_width = 0;
+            } else if line_width > 0 {
+                wrapped.push(' ');
+                line_width += 1;
+            }
+            wrapped.push_str(word);
+            line_width += word_width;
+        }
+    }
+    wrapped
+}
+
+fn render_markdown_list(document: &Document<'_>, options: &RenderOptions) -> String {
+    let mut output = ListOutput {
+        text: String::new(),
+        remaining: options.max_rows,
+        omitted: 0,
+    };
+    list_scalar(&document.root, None, 0, options, &mut output);
+
+    push_truncation_note(&mut output.text, output.omitted);
+    output.text
+}
+
+/// The nested list being written, where each value counts as a row towards
+/// `max_rows`. Once full, values are only counted and labels are left out
+struct ListOutput {
+    text: String,
+    remaining: Option<usize>,
+    omitted: usize,
+}
+
+fn list_scalar(
+    scalar: &Scalar<'_>,
+    label: Option<String>,
+    depth: usize,
+    options: &RenderOptions,
+    output: &mut ListOutput,
+) {
+    // Hidden values are skipped by their parent, as the root shares the comment
+    // above its first key and is never hidden itself
+    match &scalar.value {
+        ScalarType::Map(map) if !map.is_empty() => {
+            let depth = push_list_label(scalar, label, depth, output);
+            for entry in map.iter().filter(|entry| !is_hidden(&entry.value, options)) {
+                list_scalar(
+                    &entry.value,
+                    Some(entry.key.to_string()),
+                    depth,
+                    options,
+                    output,
+                );
+            }
+        }
+        ScalarType::List(list) if !list.is_empty() => {
+            let depth = push_list_label(scalar, label, depth, output);
             for (index, item) in list.iter().enumerate() {
-                let new_key = format!("{}.{}", key, index);
-                flatten_scalar(item, new_key, rows);
+                if is_hidden(item, options) {
+                    continue;
+                }
+                // Only nested containers need their index to anchor their children
+                let label = match item.value {
+                    ScalarType::Map(_) | ScalarType::List(_) => Some(index.to_string()),
+                    _ => None,
+                };
+                list_scalar(item, label, depth, options, output);
+            }
+        }
+        ScalarType::Map(_) => {
+            if let Some(value) = empty_container_value("{}", options) {
+                push_list_item(scalar, label, Some(value), depth, output);
+            }
+        }
+        ScalarType::List(_) => {
+            if let Some(value) = empty_container_value("[]", options) {
+                push_list_item(scalar, label, Some(value), depth, output);
             }
         }
         _ => {
-            rows.push(TableRow {
-                name: key,
-                value: format_scalar_value(&scalar.value),
-                description: scalar.comment.clone().unwrap_or_default(),
-            });
+            let value = render_value(&scalar.value, options);
+            push_list_item(scalar, label, Some(value), depth, output);
         }
     }
 }
 
-fn format_scalar_value(value: &ScalarType<'_>) -> String {
+// A container at the root of the document has no label of its own, so its
+// children are written at the top level of the list
+fn push_list_label(
+    scalar: &Scalar<'_>,
+    label: Option<String>,
+    depth: usize,
+    output: &mut ListOutput,
+) -> usize {
+    match label {
+        Some(_) => {
+            push_list_item(scalar, label, None, depth, output);
+            depth + 1
+        }
+        None => depth,
+    }
+}
+
+fn push_list_item(
+    scalar: &Scalar<'_>,
+    label: Option<String>,
+    value: Option<String>,
+    depth: usize,
+    output: &mut ListOutput,
+) {
+    let value = scalar.explicit_default.clone().or(value);
+    match output.remaining.as_mut() {
+        Some(0) if value.is_some() => {
+            output.omitted += 1;
+            return;
+        }
+        Some(0) => return,
+        Some(remaining) if value.is_some() => *remaining -= 1,
+        _ => {}
+    }
+
+    let output = &mut output.text;
+    let text = match (label, value) {
+        (Some(label), Some(value)) => format!("{}: {}", label, value),
+        (Some(label), None) => label,
+        (None, Some(value)) => value,
+        (None, None) => String::new(),
+    };
+
+    output.push_str(&"  ".repeat(depth));
+    output.push_str("- ");
+    output.push_str(&text);
+    if let Some(comment) = &scalar.comment {
+        output.push_str(" — ");
+        output.push_str(comment);
+    }
+    output.push('\n');
+}
+
+fn render_value(value: &ScalarType<'_>, options: &RenderOptions) -> String {
+    let labels = &options.labels;
+    let mut rendered = match value {
+        ScalarType::Boolean(true) => labels.true_value.clone(),
+        ScalarType::Boolean(false) => labels.false_value.clone(),
+        ScalarType::Null => labels.null_value.clone(),
+        other => format_scalar_value(other),
+    };
+
+    let is_string = matches!(value, ScalarType::String(_) | ScalarType::Text(_));
+    if options.strip_ansi && is_string {
+        rendered = ANSI_ESCAPE.replace_all(&rendered, "").into_owned();
+    }
+
+    if options.highlight_vars && is_string {
+        rendered = highlight_vars(&rendered);
+    }
+
+    if options.annotate_units
+        && let ScalarType::String(s) = value
+        && let Some(units) = describe_units(s)
+    {
+        return format!("{} ({})", rendered, units);
+    }
+    rendered
+}
+
+// Escape sequences within quoted values are decoded by the parser, so `"\e[31m"`
+// reaches here holding the raw escape character
+static ANSI_ESCAPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]").unwrap());
+
+// Matches both the braced `${VAR}` and bare `$VAR` forms of interpolation
+static VARIABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$\{[^}]*\}|\$[A-Za-z_][A-Za-z0-9_]*").unwrap());
+
+fn highlight_vars(value: &str) -> String {
+    VARIABLE.replace_all(value, "`$0`").into_owned()
+}
+
+pub(crate) fn format_scalar_value(value: &ScalarType<'_>) -> String {
     match value {
-        ScalarType::String(s) => s.to_string(), // Convert &str to String
+        ScalarType::String(s) | ScalarType::Timestamp(s) => s.to_string(), // Convert &str to String
+        ScalarType::Text(s) => s.clone(),
         ScalarType::Integer(n) => n.to_string(),
         ScalarType::Float(n) => n.to_string(),
         ScalarType::Boolean(b) => b.to_string(),
@@ -1,23 +1,244 @@
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap, sync::LazyLock};
 
 use anyhow::{Context, Result, anyhow};
-use tree_sitter::{Node, Parser};
+use regex::Regex;
+use tree_sitter::{Node, Parser, Tree};
+
+use crate::error::Result as YamResult;
 
 #[derive(Debug)]
 pub struct Document<'a> {
     pub root: Scalar<'a>,
+    /// Every `&anchor` defined within the document, in the order they appear
+    pub definitions: Vec<Definition<'a>>,
 }
 
+/// A value defined with an `&anchor`, so that it can be reused through an `*alias`
 #[derive(Debug, Clone, PartialEq)]
+pub struct Definition<'a> {
+    pub name: &'a str,
+    pub value: Scalar<'a>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Scalar<'a> {
     pub value: ScalarType<'a>,
     pub comment: Option<String>,
+    /// A value documented through a `# @default -- value` annotation
+    pub explicit_default: Option<String>,
+    /// A type documented through a `# @type -- type` annotation
+    pub explicit_type: Option<String>,
+    /// Set through a `# @required` annotation when the value must be provided
+    pub required: bool,
+    /// The anchor named by the `*alias` this value was resolved from
+    pub alias: Option<&'a str>,
+    /// How the value was written, telling a quoted `"8080"` apart from a plain `8080`
+    pub style: ScalarStyle,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl<'a> Scalar<'a> {
+    pub fn new(value: ScalarType<'a>) -> Self {
+        Self {
+            value,
+            ..Default::default()
+        }
+    }
+
+    /// Returns a copy of the scalar with the closure applied to each leaf value, preserving
+    /// the structure of any maps and lists along with all comments
+    pub fn map_values<F>(&self, mut f: F) -> Scalar<'a>
+    where
+        F: FnMut(&ScalarType<'a>) -> ScalarType<'a>,
+    {
+        self.map_values_with(&mut f)
+    }
+
+    fn map_values_with<F>(&self, f: &mut F) -> Scalar<'a>
+    where
+        F: FnMut(&ScalarType<'a>) -> ScalarType<'a>,
+    {
+        let value = match &self.value {
+            ScalarType::List(list) => {
+                ScalarType::List(list.iter().map(|item| item.map_values_with(f)).collect())
+            }
+            ScalarType::Map(map) => ScalarType::Map(
+                map.iter()
+                    .map(|entry| MapItem {
+                        key: entry.key,
+                        value: entry.value.map_values_with(f),
+                        bare_key: entry.bare_key,
+                    })
+                    .collect(),
+            ),
+            leaf => f(leaf),
+        };
+
+        Scalar {
+            value,
+            comment: self.comment.clone(),
+            explicit_default: self.explicit_default.clone(),
+            explicit_type: self.explicit_type.clone(),
+            required: self.required,
+            alias: self.alias,
+            style: self.style,
+        }
+    }
+
+    /// Looks up the value of a key when the scalar is a map
+    pub fn get(&self, key: &str) -> Option<&Scalar<'a>> {
+        match &self.value {
+            ScalarType::Map(map) => map
+                .iter()
+                .find(|entry| entry.key == key)
+                .map(|entry| &entry.value),
+            _ => None,
+        }
+    }
+
+    /// Follows a dotted path, `ingress.tls.0`, through nested maps and lists, where
+    /// list entries are addressed by index. An empty path returns the scalar itself
+    pub fn get_path(&self, path: &str) -> Option<&Scalar<'a>> {
+        self.get_path_with_base(path, 0)
+    }
+
+    /// Follows a dotted path like [`Scalar::get_path`], where list entries are
+    /// numbered from the given base, matching rows written with that index base
+    pub fn get_path_with_base(&self, path: &str, index_base: usize) -> Option<&Scalar<'a>> {
+        if path.is_empty() {
+            return Some(self);
+        }
+
+        path.split('.')
+            .try_fold(self, |scalar, segment| match &scalar.value {
+                ScalarType::List(list) => {
+                    let index = segment.parse::<usize>().ok()?.checked_sub(index_base)?;
+                    list.get(index)
+                }
+                _ => scalar.get(segment),
+            })
+    }
+
+    /// Compares the values of two scalars, ignoring their comments and any
+    /// annotations written within them
+    pub fn structurally_eq(&self, other: &Self) -> bool {
+        match (&self.value, &other.value) {
+            (ScalarType::Map(a), ScalarType::Map(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|(a, b)| a.key == b.key && a.value.structurally_eq(&b.value))
+            }
+            (ScalarType::List(a), ScalarType::List(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.structurally_eq(b))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Recursively sorts the entries of every map by key, leaving the order of
+    /// list items untouched
+    pub fn sort_maps(&mut self) {
+        match &mut self.value {
+            ScalarType::Map(map) => {
+                map.sort_by(|a, b| a.key.cmp(b.key));
+                for entry in map {
+                    entry.value.sort_maps();
+                }
+            }
+            ScalarType::List(list) => list.iter_mut().for_each(Scalar::sort_maps),
+            _ => {}
+        }
+    }
+
+    /// Recursively removes every map entry and list item whose comment contains the
+    /// marker, such as `@internal`
+    pub fn remove_marked(&mut self, marker: &str) {
+        let marked = |scalar: &Scalar<'_>| {
+            scalar
+                .comment
+                .as_ref()
+                .is_some_and(|comment| comment.contains(marker))
+        };
+
+        match &mut self.value {
+            ScalarType::Map(map) => {
+                // A comment shared with the first entry, as above a block map, goes
+                // with that entry
+                let shared = map
+                    .first()
+                    .is_some_and(|entry| entry.value.comment == self.comment);
+                map.retain(|entry| !marked(&entry.value));
+                for entry in map.iter_mut() {
+                    entry.value.remove_marked(marker);
+                }
+                if shared {
+                    self.comment = map.first().and_then(|entry| entry.value.comment.clone());
+                }
+            }
+            ScalarType::List(list) => {
+                list.retain(|item| !marked(item));
+                for item in list {
+                    item.remove_marked(marker);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the value when the scalar is a string, including one decoded from
+    /// escape sequences
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            ScalarType::String(s) => Some(s),
+            ScalarType::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Converts the scalar into its JSON representation, dropping all comments
+    pub fn to_value(&self) -> serde_json::Value {
+        match &self.value {
+            ScalarType::Null => serde_json::Value::Null,
+            ScalarType::String(s) | ScalarType::Timestamp(s) => serde_json::Value::from(*s),
+            ScalarType::Text(s) => serde_json::Value::from(s.as_str()),
+            ScalarType::Integer(n) => serde_json::Value::from(*n),
+            // JSON has no representation for infinity or NaN, which serde_json maps to null
+            ScalarType::Float(n) => serde_json::Value::from(*n),
+            ScalarType::Boolean(b) => serde_json::Value::from(*b),
+            ScalarType::List(list) => list.iter().map(Scalar::to_value).collect(),
+            ScalarType::Map(map) => serde_json::Value::Object(
+                map.iter()
+                    .map(|entry| (entry.key.to_string(), entry.value.to_value()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// The presentation of a scalar within the source. Collections, and values read from
+/// another input format, are [`ScalarStyle::Plain`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarStyle {
+    #[default]
+    Plain,
+    SingleQuoted,
+    DoubleQuoted,
+    /// A block scalar introduced by `|`
+    Literal,
+    /// A block scalar introduced by `>`
+    Folded,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
 pub enum ScalarType<'a> {
+    #[default]
     Null,
     String(&'a str),
+    /// A string that isn't written verbatim within the source, such as a block
+    /// scalar once its indentation is removed
+    Text(String),
+    /// An ISO 8601 date or date and time, `2021-04-01T10:00:00Z`, kept as written
+    Timestamp(&'a str),
     Integer(i64),
     Float(f64),
     Boolean(bool),
@@ -25,138 +246,688 @@ pub enum ScalarType<'a> {
     Map(Vec<MapItem<'a>>),
 }
 
+impl ScalarType<'_> {
+    /// A lowercase name describing the type of the scalar
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ScalarType::Null => "null",
+            ScalarType::String(_) | ScalarType::Text(_) => "string",
+            ScalarType::Timestamp(_) => "timestamp",
+            ScalarType::Integer(_) => "integer",
+            ScalarType::Float(_) => "float",
+            ScalarType::Boolean(_) => "boolean",
+            ScalarType::List(_) => "list",
+            ScalarType::Map(_) => "map",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct MapItem<'a> {
     pub key: &'a str,
     pub value: Scalar<'a>,
+    /// Set when the key was written without a `:` separator, such as
+    /// `? key` in a block mapping or `{key}` in a flow mapping
+    pub bare_key: bool,
+}
+
+impl<'a> Document<'a> {
+    /// A document without any anchor definitions
+    pub fn new(root: Scalar<'a>) -> Self {
+        Self {
+            root,
+            definitions: Vec::new(),
+        }
+    }
+
+    /// Converts the document into its JSON representation, dropping all comments
+    pub fn to_value(&self) -> serde_json::Value {
+        self.root.to_value()
+    }
+
+    /// Compares the values of two documents, ignoring their comments
+    pub fn structurally_eq(&self, other: &Self) -> bool {
+        self.root.structurally_eq(&other.root)
+    }
+
+    /// Recursively sorts the keys of every map within the document, including
+    /// those of anchor definitions
+    pub fn sort_maps(&mut self) {
+        self.root.sort_maps();
+        for definition in &mut self.definitions {
+            definition.value.sort_maps();
+        }
+    }
+
+    /// Removes every value marked as internal from the document, including those
+    /// within anchor definitions. The root itself is always kept
+    pub fn remove_marked(&mut self, marker: &str) {
+        self.root.remove_marked(marker);
+        for definition in &mut self.definitions {
+            definition.value.remove_marked(marker);
+        }
+    }
+
+    /// The maximum nesting level of maps and lists within the document, where a
+    /// document holding a single scalar has a depth of 0
+    pub fn depth(&self) -> usize {
+        scalar_depth(&self.root)
+    }
+
+    /// Identifies a Kubernetes-style resource as `kind/metadata.name`, provided
+    /// the document defines both as strings
+    pub fn resource_name(&self) -> Option<String> {
+        let kind = self.root.get("kind")?.as_str()?;
+        let name = self.root.get("metadata")?.get("name")?.as_str()?;
+        Some(format!("{}/{}", kind, name))
+    }
+}
+
+/// Decodes a single escape sequence of a quoted scalar into the character it stands for
+fn unescape(sequence: &str) -> Option<char> {
+    let c = match sequence {
+        "''" => '\'',
+        "\\0" => '\0',
+        "\\a" => '\u{7}',
+        "\\b" => '\u{8}',
+        "\\t" | "\\\t" => '\t',
+        "\\n" => '\n',
+        "\\v" => '\u{b}',
+        "\\f" => '\u{c}',
+        "\\r" => '\r',
+        "\\e" => '\u{1b}',
+        "\\ " => ' ',
+        "\\\"" => '"',
+        "\\/" => '/',
+        "\\\\" => '\\',
+        "\\N" => '\u{85}',
+        "\\_" => '\u{a0}',
+        "\\L" => '\u{2028}',
+        "\\P" => '\u{2029}',
+        _ => {
+            let hex = sequence
+                .strip_prefix("\\x")
+                .or_else(|| sequence.strip_prefix("\\u"))
+                .or_else(|| sequence.strip_prefix("\\U"))?;
+            return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+        }
+    };
+    Some(c)
+}
+
+/// Reads a string as the type named by a core schema tag, such as `!!int`
+fn coerce<'a>(tag: &str, text: &str) -> Option<ScalarType<'a>> {
+    let text = text.trim();
+    match tag {
+        "!!int" => {
+            let (negative, digits) = match text.strip_prefix('-') {
+                Some(digits) => (true, digits),
+                None => (false, text.strip_prefix('+').unwrap_or(text)),
+            };
+            let magnitude = if let Some(hex) = digits.strip_prefix("0x") {
+                i64::from_str_radix(hex, 16).ok()?
+            } else if let Some(octal) = digits.strip_prefix("0o") {
+                i64::from_str_radix(octal, 8).ok()?
+            } else if digits.starts_with(|c: char| c.is_ascii_digit()) {
+                digits.parse::<i64>().ok()?
+            } else {
+                return None;
+            };
+            Some(ScalarType::Integer(if negative {
+                -magnitude
+            } else {
+                magnitude
+            }))
+        }
+        "!!float" => {
+            let value = match text.to_lowercase().as_str() {
+                ".inf" | "+.inf" => f64::INFINITY,
+                "-.inf" => f64::NEG_INFINITY,
+                ".nan" => f64::NAN,
+                _ if text.contains(|c: char| c.is_ascii_digit()) => text.parse().ok()?,
+                _ => return None,
+            };
+            Some(ScalarType::Float(value))
+        }
+        "!!bool" => match text {
+            "true" | "True" | "TRUE" => Some(ScalarType::Boolean(true)),
+            "false" | "False" | "FALSE" => Some(ScalarType::Boolean(false)),
+            _ => None,
+        },
+        "!!null" => match text {
+            "" | "~" | "null" | "Null" | "NULL" => Some(ScalarType::Null),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// How the line breaks at the end of a block scalar are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Chomping {
+    /// Keep a single line break, the default
+    Clip,
+    /// Remove every trailing line break, `|-`
+    Strip,
+    /// Keep every trailing line break, `|+`
+    Keep,
+}
+
+/// Joins the lines of a folded block scalar. A line break between two lines of text
+/// becomes a space, unless blank lines separate them, which are kept as line breaks.
+/// Lines indented beyond the content, and the breaks around them, are left as written
+fn fold_lines(lines: &[&str]) -> String {
+    let mut folded = String::new();
+    let mut previous_indented = None;
+    let mut blank_lines = 0;
+
+    for line in lines {
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+
+        let indented = line.starts_with([' ', '\t']);
+        match previous_indented {
+            None => folded.push_str(&"\n".repeat(blank_lines)),
+            Some(false) if !indented && blank_lines == 0 => folded.push(' '),
+            Some(false) if !indented => folded.push_str(&"\n".repeat(blank_lines)),
+            Some(_) => folded.push_str(&"\n".repeat(blank_lines + 1)),
+        }
+        folded.push_str(line);
+        previous_indented = Some(indented);
+        blank_lines = 0;
+    }
+    folded
+}
+
+fn without_comments<'a>(scalar: &Scalar<'a>) -> Scalar<'a> {
+    let value = match &scalar.value {
+        ScalarType::List(list) => ScalarType::List(list.iter().map(without_comments).collect()),
+        ScalarType::Map(map) => ScalarType::Map(
+            map.iter()
+                .map(|entry| MapItem {
+                    key: entry.key,
+                    value: without_comments(&entry.value),
+                    bare_key: entry.bare_key,
+                })
+                .collect(),
+        ),
+        leaf => leaf.clone(),
+    };
+
+    Scalar {
+        value,
+        comment: None,
+        explicit_default: scalar.explicit_default.clone(),
+        explicit_type: scalar.explicit_type.clone(),
+        required: scalar.required,
+        alias: scalar.alias,
+        style: scalar.style,
+    }
+}
+
+fn scalar_depth(scalar: &Scalar<'_>) -> usize {
+    let children = match &scalar.value {
+        ScalarType::List(list) => list.iter().map(scalar_depth).max(),
+        ScalarType::Map(map) => map.iter().map(|entry| scalar_depth(&entry.value)).max(),
+        _ => return 0,
+    };
+    children.unwrap_or(0) + 1
+}
+
+impl<'a> TryFrom<&'a str> for Document<'a> {
+    type Error = ParseError;
+
+    fn try_from(text: &'a str) -> Result<Self, Self::Error> {
+        parse_source(text.as_bytes(), &ParseOptions::default())?.ok_or(ParseError::EmptyDocument)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum ParseError {
     #[error("an empty document")]
     EmptyDocument,
+    #[error("invalid UTF-8 at line {line}, column {column}")]
+    InvalidUtf8 { line: usize, column: usize },
     #[error("parsing error: {0}")]
-    Generic(#[from] anyhow::Error),
+    Generic(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl ParseError {
+    // Errors are raised through anyhow within the parser, which is kept out of the
+    // public API by boxing them
+    fn generic(error: anyhow::Error) -> Self {
+        ParseError::Generic(error.into())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Which comments are treated as descriptions
+    pub comment_style: CommentStyle,
+    /// The marker that prefixes a description when using [`CommentStyle::Helm`]
+    pub comment_marker: String,
+    /// Return a document with a null root carrying the comments of a document
+    /// that contains nothing but comments, rather than discarding it
+    pub keep_comment_only: bool,
+    /// Join the lines of a multi-line comment with a newline rather than a space
+    pub preserve_comment_breaks: bool,
+    /// Detect plain scalars written as ISO 8601 dates and times as timestamps
+    pub timestamps: bool,
+    /// Keep every group of comments above a value, separated by blank lines, as
+    /// its own paragraph of the description
+    pub merge_comment_groups: bool,
+    /// Fail when an anchor name is defined more than once within a document, rather
+    /// than resolving later aliases to the latest definition
+    pub strict_anchors: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            comment_style: CommentStyle::default(),
+            comment_marker: "--".to_string(),
+            keep_comment_only: false,
+            preserve_comment_breaks: false,
+            timestamps: true,
+            merge_comment_groups: false,
+            strict_anchors: false,
+        }
+    }
+}
+
+// Stands in for the blank line between two groups of comments, which can never be
+// mistaken for the text of a comment as that never spans more than one line
+const PARAGRAPH_BREAK: &str = "\n\n";
+
+// Only complete dates, optionally followed by a time and zone, are matched, so that
+// values such as `1-2-3` or the sexagesimal `12:34` remain strings
+pub(crate) static TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\d{4}-(0[1-9]|1[0-2])-(0[1-9]|[12]\d|3[01])([Tt ]([01]\d|2[0-3]):[0-5]\d:[0-5]\d(\.\d+)?(Z|[+-]([01]\d|2[0-3])(:?[0-5]\d)?)?)?$",
+    )
+    .expect("timestamp pattern should compile")
+});
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum CommentStyle {
+    /// Every comment is treated as a description
+    #[default]
+    Plain,
+    /// Only comments starting with a marker, `# -- description`, are treated as
+    /// descriptions, following the helm-docs convention
+    Helm,
+}
+
+#[derive(Debug, Default)]
+struct Comment {
+    /// Set when the comment trails other content on its line
+    inline: bool,
+    start_byte: usize,
+    description: Option<String>,
+    default: Option<String>,
+    type_hint: Option<String>,
+    required: bool,
 }
 
-struct YamlParser<'a> {
-    source: &'a str,
-    comments: HashMap<usize, String>,
+struct YamlParser<'a, 'o> {
+    source: &'a [u8],
+    options: &'o ParseOptions,
+    comments: HashMap<usize, Comment>,
+    /// Values defined with an `&anchor`, in the order they are parsed, so that any
+    /// later `*alias` can be resolved
+    anchors: RefCell<Vec<Definition<'a>>>,
 }
 
-impl<'a> YamlParser<'a> {
-    fn new(source: &'a str) -> Self {
+impl<'a, 'o> YamlParser<'a, 'o> {
+    fn new(source: &'a [u8], options: &'o ParseOptions) -> Self {
         Self {
             source,
+            options,
             comments: HashMap::new(),
+            anchors: RefCell::new(Vec::new()),
         }
     }
 
     fn parse(&mut self, node: &Node) -> Result<Scalar<'a>, ParseError> {
-        self.parse_comments(node);
+        self.parse_comments(node).map_err(ParseError::generic)?;
         self.parse_tree(node)
     }
 
-    fn parse_comments(&mut self, node: &Node) {
+    fn parse_documents(&mut self, node: &Node) -> Result<Vec<Document<'a>>, ParseError> {
+        self.parse_comments(node).map_err(ParseError::generic)?;
+
+        let mut documents = Vec::new();
+        let mut cursor = node.walk();
+        for document in node
+            .children(&mut cursor)
+            .filter(|child| child.kind() == "document")
+        {
+            // Anchors are scoped to the document that defines them
+            let root = self.parse_tree(&document);
+            let definitions = self.anchors.take();
+            match root {
+                Ok(root) => documents.push(Document { root, definitions }),
+                Err(ParseError::EmptyDocument) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(documents)
+    }
+
+    /// Slices the text of a node from the source, only validating that slice as UTF-8
+    fn text(&self, node: &Node) -> Result<&'a str> {
+        std::str::from_utf8(&self.source[node.byte_range()]).map_err(|_| {
+            let pos = node.start_position();
+            anyhow!(
+                "invalid UTF-8 at line {}, column {}",
+                pos.row + 1,
+                pos.column + 1
+            )
+        })
+    }
+
+    fn parse_comments(&mut self, node: &Node) -> Result<()> {
         let mut cursor = node.walk();
         let mut children = node.children(&mut cursor).peekable();
 
         while let Some(child) = children.next() {
             if child.kind() == "comment" {
-                let mut comment_parts = vec![self.extract_comment_text(&child)];
+                let mut comment_parts = Vec::from_iter(self.comment_part(&child)?);
                 let mut last_line = child.start_position().row;
+                let inline = self.is_inline_comment(&child);
 
-                while let Some(next) = children.peek() {
-                    if next.kind() == "comment" {
+                // A comment trailing a value on the same line describes only that value
+                while !inline && let Some(next) = children.peek() {
+                    if next.kind() == "comment" && !self.is_inline_comment(next) {
                         let next_child = children.next().unwrap();
-                        last_line = next_child.start_position().row;
-                        comment_parts.push(self.extract_comment_text(&next_child));
+                        let line = next_child.start_position().row;
+                        if self.options.merge_comment_groups && line > last_line + 1 {
+                            comment_parts.push(PARAGRAPH_BREAK);
+                        }
+                        last_line = line;
+                        comment_parts.extend(self.comment_part(&next_child)?);
                     } else {
                         break;
                     }
                 }
 
-                let joined_comment = comment_parts.join(" ");
-                self.comments.insert(last_line, joined_comment);
+                if let Some(mut comment) = self.build_comment(&comment_parts) {
+                    comment.inline = inline;
+                    comment.start_byte = child.start_byte();
+                    self.comments.insert(last_line, comment);
+                }
+            } else {
+                self.parse_comments(&child)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_inline_comment(&self, node: &Node) -> bool {
+        let start = node.start_byte();
+        self.source[self.line_start(start)..start]
+            .iter()
+            .any(|b| !b.is_ascii_whitespace())
+    }
+
+    fn line_start(&self, byte: usize) -> usize {
+        self.source[..byte]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1)
+    }
+
+    fn build_comment(&self, comment_parts: &[&str]) -> Option<Comment> {
+        let mut comment = Comment::default();
+        let mut lines = Vec::new();
+
+        for part in comment_parts {
+            if let Some(value) = extract_annotation(part, "@default") {
+                comment.default = Some(value.to_string());
+            } else if let Some(value) = extract_annotation(part, "@type") {
+                comment.type_hint = Some(value.to_string());
+            } else if extract_annotation(part, "@required").is_some() {
+                comment.required = true;
             } else {
-                self.parse_comments(&child);
+                lines.push(*part);
+            }
+        }
+        comment.description = self.describe(&lines);
+
+        if comment.description.is_none()
+            && comment.default.is_none()
+            && comment.type_hint.is_none()
+            && !comment.required
+        {
+            return None;
+        }
+        Some(comment)
+    }
+
+    fn describe(&self, comment_parts: &[&str]) -> Option<String> {
+        match self.options.comment_style {
+            CommentStyle::Plain => {
+                (!comment_parts.is_empty()).then(|| self.join_comment_parts(comment_parts))
+            }
+            CommentStyle::Helm => {
+                let marker = self.options.comment_marker.as_str();
+                let (start, text) = comment_parts.iter().enumerate().find_map(|(i, part)| {
+                    let text = part.strip_prefix(marker)?;
+                    (text.is_empty() || text.starts_with(char::is_whitespace))
+                        .then(|| (i, text.trim_start()))
+                })?;
+
+                // Lines following the marker continue the description
+                let mut description = vec![text];
+                description.extend(&comment_parts[start + 1..]);
+                Some(self.join_comment_parts(&description))
+            }
+        }
+    }
+
+    fn join_comment_parts(&self, comment_parts: &[&str]) -> String {
+        let mut text = String::new();
+        for (i, part) in comment_parts.iter().enumerate() {
+            if *part == PARAGRAPH_BREAK {
+                text.push_str(PARAGRAPH_BREAK);
+                continue;
+            }
+            if i > 0 && comment_parts[i - 1] != PARAGRAPH_BREAK {
+                text.push_str(self.comment_separator());
             }
+            text.push_str(part);
+        }
+        text.trim_matches('\n').to_string()
+    }
+
+    fn comment_separator(&self) -> &'static str {
+        if self.options.preserve_comment_breaks {
+            "\n"
+        } else {
+            " "
         }
     }
 
-    fn extract_comment_text(&self, node: &Node) -> &'a str {
-        let text = &self.source[node.byte_range()];
-        text.trim_start_matches('#').trim()
+    fn extract_comment_text(&self, node: &Node) -> Result<&'a str> {
+        let text = self.text(node)?;
+        Ok(text.trim_start_matches('#').trim())
+    }
+
+    // A divider, such as `####`, decorates the file rather than describing a value, so
+    // it contributes nothing to the description of the comment group around it
+    fn comment_part(&self, node: &Node) -> Result<Option<&'a str>> {
+        let text = self.text(node)?.trim_end();
+        if text.len() > 1 && text.bytes().all(|b| b == b'#') {
+            return Ok(None);
+        }
+        self.extract_comment_text(node).map(Some)
     }
 
-    fn find_comment_for_node(&self, node: &Node) -> Option<String> {
+    fn find_comment_for_node(&self, node: &Node) -> Option<&Comment> {
         let line_number = node.start_position().row;
 
-        if let Some(comment) = self.comments.get(&line_number) {
-            return Some(comment.clone());
+        if let Some(comment) = self.comments.get(&line_number)
+            && (!comment.inline || self.trails_node(comment, node))
+        {
+            return Some(comment);
         }
 
         if line_number > 0
             && let Some(comment) = self.comments.get(&(line_number - 1))
+            && (!comment.inline || self.follows_key(comment))
         {
-            return Some(comment.clone());
+            return Some(comment);
         }
 
         None
     }
 
+    // A comment trailing a key without a value, `key: # comment`, describes the value
+    // that starts on the next line
+    fn follows_key(&self, comment: &Comment) -> bool {
+        let before = &self.source[..comment.start_byte];
+        let line_start = before
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        before[line_start..].trim_ascii_end().ends_with(b":")
+    }
+
+    // Only the last node before a trailing comment claims it, so the items of a flow
+    // collection don't inherit a comment written after its closing bracket
+    fn trails_node(&self, comment: &Comment, node: &Node) -> bool {
+        let end = node.end_byte();
+        end > comment.start_byte
+            || self.source[end..comment.start_byte]
+                .iter()
+                .all(|&b| b == b',' || b.is_ascii_whitespace())
+    }
+
+    fn comment_only_document(&self) -> Option<Document<'a>> {
+        let mut lines: Vec<_> = self.comments.iter().collect();
+        lines.sort_by_key(|(line, _)| **line);
+
+        let description = lines
+            .iter()
+            .filter_map(|(_, comment)| comment.description.as_deref())
+            .collect::<Vec<_>>()
+            .join(self.comment_separator());
+        if description.is_empty() {
+            return None;
+        }
+
+        let mut root = Scalar::new(ScalarType::Null);
+        root.comment = Some(description);
+        Some(Document::new(root))
+    }
+
+    fn annotate(&self, scalar: &mut Scalar<'a>, node: &Node) {
+        let comment = self.find_comment_for_node(node);
+        scalar.comment = comment.and_then(|c| c.description.clone());
+        scalar.explicit_default = comment.and_then(|c| c.default.clone());
+        scalar.explicit_type = comment.and_then(|c| c.type_hint.clone());
+        scalar.required = comment.is_some_and(|c| c.required);
+    }
+
     fn parse_tree(&self, node: &Node) -> Result<Scalar<'a>, ParseError> {
         let mut cursor = node.walk();
+        let mut anchor = None;
+        let mut tag = None;
 
         for child in node.children(&mut cursor) {
             match child.kind() {
                 "document" | "stream" => return self.parse_tree(&child),
-                "-" | "comment" => {}
+                "-" | "---" | "..." | "comment" => {}
+                "anchor" => anchor = Some(child),
+                "tag" => tag = Some(child),
                 _ => {
-                    let mut scalar = self.parse_value(child).map_err(ParseError::Generic)?;
+                    let slot = self.anchors.borrow().len();
+                    let mut scalar = self.parse_value(child).map_err(ParseError::generic)?;
+                    if let Some(tag) = tag {
+                        scalar = self
+                            .apply_tag(tag, Some(child), scalar)
+                            .map_err(ParseError::generic)?;
+                    }
                     if scalar.comment.is_none() {
-                        scalar.comment = self.find_comment_for_node(&child);
+                        self.annotate(&mut scalar, &child);
                     }
 
+                    self.define_anchor(anchor, slot, &scalar)
+                        .map_err(ParseError::generic)?;
                     return Ok(scalar);
                 }
             }
         }
 
+        // An anchor or tag without a value, `key: &anchor`, applies to a null
+        if anchor.is_some() || tag.is_some() {
+            let mut scalar = Scalar::new(ScalarType::Null);
+            if let Some(tag) = tag {
+                scalar = self
+                    .apply_tag(tag, None, scalar)
+                    .map_err(ParseError::generic)?;
+            }
+            let slot = self.anchors.borrow().len();
+            self.define_anchor(anchor, slot, &scalar)
+                .map_err(ParseError::generic)?;
+            return Ok(scalar);
+        }
+
         Err(ParseError::EmptyDocument)
     }
 
+    // The definition takes the slot reserved before its value was parsed, so it comes
+    // ahead of any anchor nested within that value
+    fn define_anchor(&self, anchor: Option<Node>, slot: usize, scalar: &Scalar<'a>) -> Result<()> {
+        let Some(anchor) = anchor else {
+            return Ok(());
+        };
+
+        let name = self.child_name(&anchor, "anchor_name")?;
+        let mut anchors = self.anchors.borrow_mut();
+        if self.options.strict_anchors && anchors.iter().any(|d| d.name == name) {
+            let pos = anchor.start_position();
+            return Err(anyhow!(
+                "duplicate anchor &{} at line {}, column {}",
+                name,
+                pos.row + 1,
+                pos.column + 1
+            ));
+        }
+
+        anchors.insert(
+            slot,
+            Definition {
+                name,
+                value: scalar.clone(),
+            },
+        );
+        Ok(())
+    }
+
     fn parse_value(&self, node: Node) -> Result<Scalar<'a>> {
         match node.kind() {
-            "flow_node" | "block_node" => {
-                let value = node
-                    .child(0)
-                    .ok_or_else(|| anyhow!("flow_node/block_node should have a child"))?;
-                self.parse_value(value)
-            }
+            "flow_node" | "block_node" => self.parse_node(node),
+            "alias" => self.parse_alias(node),
             "plain_scalar" => self.parse_plain_scalar(node),
             "single_quote_scalar" | "double_quote_scalar" => self.parse_quoted_scalar(node),
             "block_scalar" => self.parse_block_scalar(node),
             "block_sequence" => {
                 let scalar_items = self.parse_block_sequence(node)?;
-                Ok(Scalar {
-                    value: ScalarType::List(scalar_items),
-                    comment: None,
-                })
+                Ok(Scalar::new(ScalarType::List(scalar_items)))
             }
             "block_mapping" | "flow_mapping" => {
                 let map_items = self.parse_mapping(node)?;
-                Ok(Scalar {
-                    value: ScalarType::Map(map_items),
-                    comment: None,
-                })
+                Ok(Scalar::new(ScalarType::Map(map_items)))
             }
             "flow_sequence" => {
                 let scalar_items = self.parse_flow_sequence(node)?;
-                Ok(Scalar {
-                    value: ScalarType::List(scalar_items),
-                    comment: None,
-                })
+                Ok(Scalar::new(ScalarType::List(scalar_items)))
             }
             _ => {
                 let pos = node.start_position();
@@ -170,29 +941,294 @@ impl<'a> YamlParser<'a> {
         }
     }
 
+    fn parse_node(&self, node: Node) -> Result<Scalar<'a>> {
+        let mut cursor = node.walk();
+        let mut anchor = None;
+        let mut tag = None;
+        let mut value = None;
+
+        // Tags and comments may sit between the node's properties and its content,
+        // `key: !!map # comment`, so the first child is not always the value
+        for child in node.children(&mut cursor) {
+            match child.kind() {
+                "anchor" => anchor = Some(child),
+                "tag" => tag = Some(child),
+                "comment" => {}
+                _ => {
+                    value = Some(child);
+                    break;
+                }
+            }
+        }
+
+        let slot = self.anchors.borrow().len();
+        let scalar = match value {
+            Some(value) => self.parse_value(value)?,
+            None if anchor.is_some() || tag.is_some() => Scalar::new(ScalarType::Null),
+            None => return Err(anyhow!("flow_node/block_node should have a child")),
+        };
+        let scalar = match tag {
+            Some(tag) => self.apply_tag(tag, value, scalar)?,
+            None => scalar,
+        };
+
+        self.define_anchor(anchor, slot, &scalar)?;
+        Ok(scalar)
+    }
+
+    // Only the core schema tags change how a value is read. Any other tag, such as
+    // `!!map` or an application specific `!secret`, leaves the value as it was parsed
+    fn apply_tag(&self, tag: Node, value: Option<Node>, scalar: Scalar<'a>) -> Result<Scalar<'a>> {
+        let name = self.text(&tag)?;
+        let coerced = match (name, &scalar.value) {
+            ("!!str", ScalarType::String(_) | ScalarType::Text(_)) => return Ok(scalar),
+            ("!!str", ScalarType::Map(_) | ScalarType::List(_)) => None,
+            ("!!str", _) => Some(ScalarType::String(match value {
+                Some(value) => self.text(&value)?.trim(),
+                None => "",
+            })),
+            ("!!int", ScalarType::Integer(_))
+            | ("!!float", ScalarType::Float(_))
+            | ("!!bool", ScalarType::Boolean(_))
+            | ("!!null", ScalarType::Null) => return Ok(scalar),
+            ("!!float", ScalarType::Integer(n)) => Some(ScalarType::Float(*n as f64)),
+            ("!!int" | "!!float" | "!!bool" | "!!null", ScalarType::String(text)) => {
+                coerce(name, text)
+            }
+            ("!!int" | "!!float" | "!!bool" | "!!null", ScalarType::Text(text)) => {
+                coerce(name, text)
+            }
+            ("!!int" | "!!float" | "!!bool" | "!!null", _) => None,
+            _ => return Ok(scalar),
+        };
+
+        let value = coerced.ok_or_else(|| {
+            let pos = tag.start_position();
+            anyhow!(
+                "cannot read value as {} at line {}, column {}",
+                name,
+                pos.row + 1,
+                pos.column + 1
+            )
+        })?;
+        Ok(Scalar { value, ..scalar })
+    }
+
+    // Comments describe the anchored definition, so they aren't repeated on each alias
+    fn parse_alias(&self, node: Node) -> Result<Scalar<'a>> {
+        let name = self.child_name(&node, "alias_name")?;
+        // A redefined anchor replaces the earlier definition for any later alias
+        let anchors = self.anchors.borrow();
+        let definition = anchors
+            .iter()
+            .rev()
+            .find(|d| d.name == name)
+            .ok_or_else(|| {
+                let pos = node.start_position();
+                anyhow!(
+                    "undefined alias *{} at line {}, column {}",
+                    name,
+                    pos.row + 1,
+                    pos.column + 1
+                )
+            })?;
+        Ok(Scalar {
+            alias: Some(name),
+            ..without_comments(&definition.value)
+        })
+    }
+
+    fn child_name(&self, node: &Node, kind: &str) -> Result<&'a str> {
+        let mut cursor = node.walk();
+        let name = node
+            .children(&mut cursor)
+            .find(|child| child.kind() == kind)
+            .ok_or_else(|| anyhow!("{} should have a {}", node.kind(), kind))?;
+        self.text(&name)
+    }
+
+    // The grammar marks each escape sequence, `\n` or the `''` of a single-quoted
+    // scalar, as a child node. A scalar without any is borrowed as written
     fn parse_quoted_scalar(&self, node: Node) -> Result<Scalar<'a>> {
-        let text = &self.source[node.byte_range()];
+        let text = self.text(&node)?;
+        let style = if node.kind() == "single_quote_scalar" {
+            ScalarStyle::SingleQuoted
+        } else {
+            ScalarStyle::DoubleQuoted
+        };
+        let mut cursor = node.walk();
+        let escapes: Vec<Node> = node
+            .children(&mut cursor)
+            .filter(|child| child.kind() == "escape_sequence")
+            .collect();
+        if escapes.is_empty() {
+            return Ok(Scalar {
+                style,
+                ..Scalar::new(ScalarType::String(&text[1..text.len() - 1]))
+            });
+        }
+
+        let base = node.start_byte();
+        let mut unescaped = String::new();
+        let mut offset = 1;
+        let mut line_continues = false;
+        for escape in escapes {
+            let literal = &text[offset..escape.start_byte() - base];
+            unescaped.push_str(if line_continues {
+                literal.trim_start()
+            } else {
+                literal
+            });
+
+            // An escaped line break joins the next line, without its indentation
+            let sequence = self.text(&escape)?;
+            line_continues = sequence == "\\";
+            if !line_continues {
+                unescaped.push(unescape(sequence).ok_or_else(|| {
+                    let pos = escape.start_position();
+                    anyhow!(
+                        "invalid escape sequence {} at line {}, column {}",
+                        sequence,
+                        pos.row + 1,
+                        pos.column + 1
+                    )
+                })?);
+            }
+            offset = escape.end_byte() - base;
+        }
+
+        let literal = &text[offset..text.len() - 1];
+        unescaped.push_str(if line_continues {
+            literal.trim_start()
+        } else {
+            literal
+        });
         Ok(Scalar {
-            value: ScalarType::String(&text[1..text.len() - 1]),
-            comment: None,
+            style,
+            ..Scalar::new(ScalarType::Text(unescaped))
         })
     }
 
     fn parse_block_scalar(&self, node: Node) -> Result<Scalar<'a>> {
-        let text = &self.source[node.byte_range()];
-
-        if let Some(newline_pos) = text.find('\n') {
-            let content = &text[newline_pos + 1..];
-            Ok(Scalar {
-                value: ScalarType::String(content),
-                comment: None,
-            })
+        let text = self.text(&node)?;
+        let style = if text.starts_with('>') {
+            ScalarStyle::Folded
         } else {
-            Ok(Scalar {
-                value: ScalarType::String(""),
-                comment: None,
+            ScalarStyle::Literal
+        };
+        let Some(newline_pos) = text.find('\n') else {
+            return Ok(Scalar {
+                style,
+                ..Scalar::new(ScalarType::String(""))
+            });
+        };
+
+        // The header holds an optional indentation indicator, `|2`, and chomping
+        // indicator, `|-` or `|+`, in either order
+        let header = text[..newline_pos]
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        let mut indicator = None;
+        let mut chomping = Chomping::Clip;
+        for c in header.chars().skip(1) {
+            match c {
+                '-' => chomping = Chomping::Strip,
+                '+' => chomping = Chomping::Keep,
+                _ => indicator = c.to_digit(10).or(indicator),
+            }
+        }
+
+        let start = node.start_byte() + newline_pos + 1;
+        let end = self.block_scalar_end(node.end_byte());
+        let body = std::str::from_utf8(&self.source[start..end]).map_err(|_| {
+            let pos = node.start_position();
+            anyhow!(
+                "invalid UTF-8 at line {}, column {}",
+                pos.row + 1,
+                pos.column + 1
+            )
+        })?;
+        let lines: Vec<&str> = body.split_inclusive('\n').collect();
+
+        // An indentation indicator sets the indentation of the content relative to
+        // the line holding the header, otherwise it is taken from the first line
+        let indent = match indicator {
+            Some(indicator) => {
+                let line_start = self.line_start(node.start_byte());
+                let parent_indent = self.source[line_start..]
+                    .iter()
+                    .take_while(|&&b| b == b' ')
+                    .count();
+                parent_indent + indicator as usize
+            }
+            None => lines
+                .iter()
+                .find(|line| !line.trim().is_empty())
+                .map_or(0, |line| line.len() - line.trim_start_matches(' ').len()),
+        };
+
+        let Some(last) = lines.iter().rposition(|line| !line.trim().is_empty()) else {
+            let breaks = lines.iter().filter(|line| line.ends_with('\n')).count();
+            let value = match chomping {
+                Chomping::Keep => "\n".repeat(breaks),
+                Chomping::Clip | Chomping::Strip => String::new(),
+            };
+            return Ok(Scalar {
+                style,
+                ..Scalar::new(ScalarType::Text(value))
+            });
+        };
+
+        let content: Vec<&str> = lines[..=last]
+            .iter()
+            .map(|line| {
+                let line = line.trim_end_matches(['\n', '\r']);
+                let spaces = line.bytes().take(indent).take_while(|&b| b == b' ').count();
+                &line[spaces..]
             })
+            .collect();
+        let mut value = match style {
+            ScalarStyle::Folded => fold_lines(&content),
+            _ => content.join("\n"),
+        };
+
+        // The line break ending the last line of content, followed by those of any
+        // trailing blank lines
+        let final_break = lines[last].ends_with('\n');
+        let trailing_breaks = lines[last + 1..]
+            .iter()
+            .filter(|line| line.ends_with('\n'))
+            .count();
+        match chomping {
+            Chomping::Strip => {}
+            Chomping::Clip if final_break => value.push('\n'),
+            Chomping::Clip => {}
+            Chomping::Keep => {
+                if final_break {
+                    value.push('\n');
+                }
+                value.push_str(&"\n".repeat(trailing_breaks));
+            }
         }
+
+        Ok(Scalar {
+            style,
+            ..Scalar::new(ScalarType::Text(value))
+        })
+    }
+
+    // The node of a block scalar ends with its last line of content, but any blank
+    // lines that follow still belong to it, as the line breaks kept by `+` chomping
+    fn block_scalar_end(&self, node_end: usize) -> usize {
+        let mut end = node_end;
+        for line in self.source[node_end..].split_inclusive(|&b| b == b'\n') {
+            if !line.iter().all(u8::is_ascii_whitespace) {
+                break;
+            }
+            end += line.len();
+        }
+        end
     }
 
     fn parse_plain_scalar(&self, node: Node) -> Result<Scalar<'a>> {
@@ -202,7 +1238,7 @@ impl<'a> YamlParser<'a> {
 
         match scalar.kind() {
             "integer_scalar" => {
-                let text = &self.source[scalar.byte_range()];
+                let text = self.text(&scalar)?;
                 let pos = scalar.start_position();
 
                 let parse_int = |num_str: &str, radix: u32, format: &str| {
@@ -216,35 +1252,37 @@ impl<'a> YamlParser<'a> {
                     })
                 };
 
-                let value = if text.len() > 2 {
-                    match &text[..2].to_ascii_lowercase()[..] {
-                        "0x" => parse_int(&text[2..], 16, "hexadecimal")?,
-                        "0o" => parse_int(&text[2..], 8, "octal")?,
-                        _ => text.parse::<i64>().map_err(|_| {
-                            anyhow!(
-                                "invalid integer at line {}, column {}",
-                                pos.row + 1,
-                                pos.column + 1
-                            )
-                        })?,
+                // Depending on how the grammar classifies it, scientific notation such as
+                // `1e3` may arrive here and is retried as a float
+                let parse_decimal = |num_str: &str| -> Result<ScalarType<'a>> {
+                    if let Ok(n) = num_str.parse::<i64>() {
+                        return Ok(ScalarType::Integer(n));
                     }
-                } else {
-                    text.parse::<i64>().map_err(|_| {
-                        anyhow!(
+
+                    match num_str.parse::<f64>() {
+                        Ok(n) if num_str.contains(['e', 'E', '.']) => Ok(ScalarType::Float(n)),
+                        _ => Err(anyhow!(
                             "invalid integer at line {}, column {}",
                             pos.row + 1,
                             pos.column + 1
-                        )
-                    })?
+                        )),
+                    }
                 };
 
-                Ok(Scalar {
-                    value: ScalarType::Integer(value),
-                    comment: None,
-                })
+                let value = if text.len() > 2 {
+                    match &text[..2].to_ascii_lowercase()[..] {
+                        "0x" => ScalarType::Integer(parse_int(&text[2..], 16, "hexadecimal")?),
+                        "0o" => ScalarType::Integer(parse_int(&text[2..], 8, "octal")?),
+                        _ => parse_decimal(text)?,
+                    }
+                } else {
+                    parse_decimal(text)?
+                };
+
+                Ok(Scalar::new(value))
             }
             "float_scalar" => {
-                let text = &self.source[scalar.byte_range()];
+                let text = self.text(&scalar)?;
                 let value = match text.to_lowercase().as_str() {
                     ".inf" => f64::INFINITY,
                     "-.inf" => f64::NEG_INFINITY,
@@ -259,13 +1297,10 @@ impl<'a> YamlParser<'a> {
                     })?,
                 };
 
-                Ok(Scalar {
-                    value: ScalarType::Float(value),
-                    comment: None,
-                })
+                Ok(Scalar::new(ScalarType::Float(value)))
             }
             "boolean_scalar" => {
-                let text = &self.source[scalar.byte_range()];
+                let text = self.text(&scalar)?;
                 let value = text.parse::<bool>().map_err(|_| {
                     let pos = scalar.start_position();
                     anyhow!(
@@ -274,22 +1309,16 @@ impl<'a> YamlParser<'a> {
                         pos.column + 1
                     )
                 })?;
-                Ok(Scalar {
-                    value: ScalarType::Boolean(value),
-                    comment: None,
-                })
+                Ok(Scalar::new(ScalarType::Boolean(value)))
             }
             "string_scalar" => {
-                let text = &self.source[scalar.byte_range()];
-                Ok(Scalar {
-                    value: ScalarType::String(text),
-                    comment: None,
-                })
+                let text = self.text(&scalar)?;
+                if self.options.timestamps && TIMESTAMP.is_match(text) {
+                    return Ok(Scalar::new(ScalarType::Timestamp(text)));
+                }
+                Ok(Scalar::new(ScalarType::String(text)))
             }
-            "null_scalar" => Ok(Scalar {
-                value: ScalarType::Null,
-                comment: None,
-            }),
+            "null_scalar" => Ok(Scalar::new(ScalarType::Null)),
             _ => {
                 let pos = scalar.start_position();
                 Err(anyhow!(
@@ -321,6 +1350,8 @@ impl<'a> YamlParser<'a> {
     fn parse_mapping(&self, node: Node) -> Result<Vec<MapItem<'a>>> {
         let mut cursor = node.walk();
         let mut items = Vec::new();
+        // Entries brought in through a `<<` merge key, and where they are placed
+        let mut merged: Option<(usize, Vec<MapItem<'a>>)> = None;
 
         for child in node.children(&mut cursor) {
             match child.kind() {
@@ -328,32 +1359,97 @@ impl<'a> YamlParser<'a> {
                     let key_node = child
                         .child_by_field_name("key")
                         .ok_or_else(|| anyhow!("mandatory map key is missing"))?;
+                    if self.text(&key_node)?.trim() == "<<" {
+                        let (_, entries) = merged.get_or_insert_with(|| (items.len(), Vec::new()));
+                        self.merge_entries(&child, entries)?;
+                        continue;
+                    }
                     let key = self.parse_key_as_str(&key_node)?;
 
                     let value = match child.child_by_field_name("value") {
-                        Some(value_node) => self.parse_tree(&value_node)?,
-                        None => Scalar {
-                            value: ScalarType::Null,
-                            comment: None,
-                        },
+                        Some(value_node) => {
+                            let mut value = self.parse_tree(&value_node)?;
+
+                            // A nested container starts on the line after its key, which would
+                            // otherwise claim the comment belonging to its first child
+                            if matches!(value.value, ScalarType::Map(_) | ScalarType::List(_)) {
+                                self.annotate(&mut value, &child);
+                            }
+                            value
+                        }
+                        None => {
+                            let mut value = Scalar::new(ScalarType::Null);
+                            self.annotate(&mut value, &child);
+                            value
+                        }
                     };
-                    items.push(MapItem { key, value });
+
+                    let mut cursor = child.walk();
+                    let bare_key = !child.children(&mut cursor).any(|c| c.kind() == ":");
+                    items.push(MapItem {
+                        key,
+                        value,
+                        bare_key,
+                    });
                 }
                 "flow_node" => {
                     let key = self.parse_key_as_str(&child)?;
-                    let value = Scalar {
-                        value: ScalarType::Null,
-                        comment: None,
-                    };
-                    items.push(MapItem { key, value });
+                    let mut value = Scalar::new(ScalarType::Null);
+                    self.annotate(&mut value, &child);
+                    items.push(MapItem {
+                        key,
+                        value,
+                        bare_key: true,
+                    });
                 }
                 _ => {}
             }
         }
 
+        // Keys written within the map take precedence over any that are merged into it
+        if let Some((position, entries)) = merged {
+            let entries: Vec<MapItem<'a>> = entries
+                .into_iter()
+                .filter(|entry| !items.iter().any(|item| item.key == entry.key))
+                .collect();
+            items.splice(position..position, entries);
+        }
+
         Ok(items)
     }
 
+    // The value of a merge key is either a single map or a list of them. An earlier
+    // map in the list takes precedence over a later one for any key they share
+    fn merge_entries(&self, pair: &Node, merged: &mut Vec<MapItem<'a>>) -> Result<()> {
+        let value = match pair.child_by_field_name("value") {
+            Some(value_node) => self.parse_tree(&value_node)?,
+            None => Scalar::new(ScalarType::Null),
+        };
+        let maps = match value.value {
+            ScalarType::List(list) => list,
+            _ => vec![value],
+        };
+
+        for map in maps {
+            let ScalarType::Map(entries) = map.value else {
+                let pos = pair.start_position();
+                return Err(anyhow!(
+                    "merge key << expects a map or a list of maps at line {}, column {}",
+                    pos.row + 1,
+                    pos.column + 1
+                ));
+            };
+            for mut entry in entries {
+                if merged.iter().any(|existing| existing.key == entry.key) {
+                    continue;
+                }
+                entry.value.alias = entry.value.alias.or(map.alias);
+                merged.push(entry);
+            }
+        }
+        Ok(())
+    }
+
     fn parse_key_as_str(&self, node: &Node) -> Result<&'a str> {
         match node.kind() {
             "flow_node" | "block_node" => {
@@ -364,22 +1460,79 @@ impl<'a> YamlParser<'a> {
                 }
             }
             "plain_scalar" => {
-                if let Some(child) = node.child(0) {
-                    Ok(&self.source[child.byte_range()])
+                let text = if let Some(child) = node.child(0) {
+                    self.text(&child)?
                 } else {
-                    Ok(&self.source[node.byte_range()])
-                }
+                    self.text(node)?
+                };
+                Ok(text.trim())
             }
             "single_quote_scalar" | "double_quote_scalar" => {
-                let text = &self.source[node.byte_range()];
+                let text = self.text(node)?;
                 Ok(&text[1..text.len() - 1])
             }
-            _ => Ok(&self.source[node.byte_range()]),
+            _ => Ok(self.text(node)?),
+        }
+    }
+}
+
+/// Extracts the value of a helm-docs style annotation, `@name -- value`
+fn extract_annotation<'t>(text: &'t str, name: &str) -> Option<&'t str> {
+    let value = text.strip_prefix(name)?;
+    if !value.is_empty() && !value.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let value = value.trim_start();
+    Some(value.strip_prefix("--").unwrap_or(value).trim())
+}
+
+pub fn parse(text: &str) -> YamResult<Option<Document<'_>>> {
+    parse_with_options(text, &ParseOptions::default())
+}
+
+pub fn parse_with_options<'a>(
+    text: &'a str,
+    options: &ParseOptions,
+) -> YamResult<Option<Document<'a>>> {
+    Ok(parse_source(text.as_bytes(), options)?)
+}
+
+/// Parses a YAML document held as raw bytes. Rather than validating the entire
+/// input upfront, only the slices extracted into the document are checked to be
+/// valid UTF-8
+pub fn parse_bytes(bytes: &[u8]) -> YamResult<Option<Document<'_>>> {
+    Ok(parse_source(bytes, &ParseOptions::default())?)
+}
+
+/// Parses every document within a multi-document stream, skipping any that are empty
+pub fn parse_all(text: &str) -> YamResult<Vec<Document<'_>>> {
+    parse_all_with_options(text, &ParseOptions::default())
+}
+
+pub fn parse_all_with_options<'a>(
+    text: &'a str,
+    options: &ParseOptions,
+) -> YamResult<Vec<Document<'a>>> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let source = text.as_bytes();
+    let tree = parse_syntax_tree(source).map_err(ParseError::generic)?;
+    let mut yaml_parser = YamlParser::new(source, options);
+
+    match yaml_parser.parse_documents(&tree.root_node()) {
+        Ok(documents) if documents.is_empty() && options.keep_comment_only => {
+            Ok(yaml_parser.comment_only_document().into_iter().collect())
         }
+        Ok(documents) => Ok(documents),
+        Err(ParseError::EmptyDocument) => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
     }
 }
 
-pub fn parse(text: &str) -> Result<Option<Document<'_>>> {
+fn parse_syntax_tree(source: &[u8]) -> Result<Tree> {
     let mut parser = Parser::new();
     let language = tree_sitter_yaml::LANGUAGE;
 
@@ -387,16 +1540,50 @@ pub fn parse(text: &str) -> Result<Option<Document<'_>>> {
         .set_language(&language.into())
         .context("failed to set YAML language")?;
 
-    let tree = parser
-        .parse(text, None)
-        .ok_or_else(|| anyhow!("failed to parse YAML document"))?;
+    parser
+        .parse(source, None)
+        .ok_or_else(|| anyhow!("failed to parse YAML document"))
+}
+
+fn parse_source<'a>(
+    source: &'a [u8],
+    options: &ParseOptions,
+) -> Result<Option<Document<'a>>, ParseError> {
+    // Skip setting up the parser when there is nothing to parse
+    if source.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
 
+    let tree = parse_syntax_tree(source).map_err(ParseError::generic)?;
     let root_node = tree.root_node();
-    let mut yaml_parser = YamlParser::new(text);
+    let mut yaml_parser = YamlParser::new(source, options);
 
     match yaml_parser.parse(&root_node) {
-        Ok(root_scalar) => Ok(Some(Document { root: root_scalar })),
+        Ok(root) => Ok(Some(Document {
+            root,
+            definitions: yaml_parser.anchors.take(),
+        })),
+        Err(ParseError::EmptyDocument) if options.keep_comment_only => {
+            Ok(yaml_parser.comment_only_document())
+        }
         Err(ParseError::EmptyDocument) => Ok(None),
-        Err(ParseError::Generic(e)) => Err(e),
+        Err(ParseError::Generic(e)) => {
+            Err(invalid_utf8_error(source).unwrap_or(ParseError::Generic(e)))
+        }
+        Err(e) => Err(e),
     }
 }
+
+// An invalid UTF-8 sequence within a scalar causes tree-sitter to produce an error
+// node, so the source is only fully validated once parsing has already failed
+fn invalid_utf8_error(source: &[u8]) -> Option<ParseError> {
+    let error = std::str::from_utf8(source).err()?;
+    let valid = &source[..error.valid_up_to()];
+
+    let line = valid.iter().filter(|&&b| b == b'\n').count();
+    let line_start = valid.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    Some(ParseError::InvalidUtf8 {
+        line: line + 1,
+        column: valid.len() - line_start + 1,
+    })
+}
@@ -0,0 +1,59 @@
+use crate::{
+    error::Result,
+    markdown::{Flattened, RenderOptions, flatten_document, truncation_note},
+    parser::Document,
+};
+
+/// Writes each value as a `dotted.key=value` line of a Java properties file, preceded
+/// by its description as `#` comment lines
+pub fn render_properties(document: &Document<'_>, options: &RenderOptions) -> Result<String> {
+    let Flattened { rows, omitted } = flatten_document(document, options);
+
+    let mut output = String::new();
+    for row in rows {
+        for line in row.description.lines() {
+            output.push_str(&format!("# {}\n", line));
+        }
+        output.push_str(&format!(
+            "{}={}\n",
+            escape(&row.name, true),
+            escape(&row.value, false)
+        ));
+    }
+
+    // A comment keeps the note from being read as a property
+    if omitted > 0 {
+        output.push_str(&format!("# {}\n", truncation_note(omitted)));
+    }
+    Ok(output)
+}
+
+// Spaces only separate a key from its value, or are stripped from the start of a
+// value, so they are escaped throughout a key but only when leading a value.
+// Properties files are read as ISO 8859-1, so anything beyond ASCII is written as
+// UTF-16 `\uXXXX` escapes
+fn escape(text: &str, key: bool) -> String {
+    let mut escaped = String::new();
+    for (index, c) in text.chars().enumerate() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\x0c' => escaped.push_str("\\f"),
+            '=' | ':' | '#' | '!' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' ' if key || index == 0 => escaped.push_str("\\ "),
+            c if c.is_ascii_control() || !c.is_ascii() => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    escaped.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
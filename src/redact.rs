@@ -0,0 +1,102 @@
+use regex::Regex;
+
+use crate::{
+    error::{Result, YamError},
+    markdown::{RenderOptions, list_item_key, map_entry_key},
+    parser::{Definition, Document, MapItem, Scalar, ScalarType},
+};
+
+pub const REDACTED: &str = "***";
+
+/// Compiles patterns that must match an entire dotted path, `database.password`
+pub fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(&format!("^(?:{})$", pattern)).map_err(|source| YamError::Pattern {
+                pattern: pattern.clone(),
+                source,
+            })
+        })
+        .collect()
+}
+
+/// Returns a copy of the document with every leaf value whose dotted path matches
+/// one of the patterns replaced by [`REDACTED`]. Paths are named as the rows of the
+/// table are, following the index style and base of the options. Anchor definitions
+/// are matched as though rooted at their anchor name, which usually mirrors the key
+/// they sit under
+pub fn redact_document<'a>(
+    document: &Document<'a>,
+    patterns: &[Regex],
+    options: &RenderOptions,
+) -> Document<'a> {
+    let redact = |scalar, path| redact_scalar(scalar, path, patterns, options);
+    Document {
+        root: redact(&document.root, String::new()),
+        definitions: document
+            .definitions
+            .iter()
+            .map(|definition| Definition {
+                name: definition.name,
+                value: redact(&definition.value, definition.name.to_string()),
+            })
+            .collect(),
+    }
+}
+
+fn redact_scalar<'a>(
+    scalar: &Scalar<'a>,
+    path: String,
+    patterns: &[Regex],
+    options: &RenderOptions,
+) -> Scalar<'a> {
+    let value = match &scalar.value {
+        ScalarType::Map(map) => ScalarType::Map(
+            map.iter()
+                .map(|entry| MapItem {
+                    key: entry.key,
+                    value: redact_scalar(
+                        &entry.value,
+                        map_entry_key(&path, entry.key),
+                        patterns,
+                        options,
+                    ),
+                    bare_key: entry.bare_key,
+                })
+                .collect(),
+        ),
+        ScalarType::List(list) => ScalarType::List(
+            list.iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    let path = list_item_key(&path, index, options);
+                    redact_scalar(item, path, patterns, options)
+                })
+                .collect(),
+        ),
+        _ if patterns.iter().any(|pattern| pattern.is_match(&path)) => {
+            // An annotated default is rendered in place of the value, so it must be masked too
+            return Scalar {
+                value: ScalarType::String(REDACTED),
+                comment: scalar.comment.clone(),
+                explicit_default: None,
+                explicit_type: scalar.explicit_type.clone(),
+                required: scalar.required,
+                alias: scalar.alias,
+                style: scalar.style,
+            };
+        }
+        leaf => leaf.clone(),
+    };
+
+    Scalar {
+        value,
+        comment: scalar.comment.clone(),
+        explicit_default: scalar.explicit_default.clone(),
+        explicit_type: scalar.explicit_type.clone(),
+        required: scalar.required,
+        alias: scalar.alias,
+        style: scalar.style,
+    }
+}
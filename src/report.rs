@@ -0,0 +1,100 @@
+use std::{cmp::Reverse, fmt};
+
+use crate::parser::{Document, Scalar, ScalarType};
+
+/// The number of leaf values within a document of a single type
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeCount {
+    pub type_name: &'static str,
+    pub count: usize,
+}
+
+impl fmt::Display for TypeCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s: {}", self.type_name, self.count)
+    }
+}
+
+/// Tallies the leaf values of a document by type, most frequent first. Maps and
+/// lists are only counted through the values they hold
+pub fn count_types(document: &Document<'_>) -> Vec<TypeCount> {
+    let mut counts: Vec<TypeCount> = Vec::new();
+    count_scalar(&document.root, &mut counts);
+
+    counts.sort_by_key(|c| Reverse(c.count));
+    counts
+}
+
+fn count_scalar(scalar: &Scalar<'_>, counts: &mut Vec<TypeCount>) {
+    match &scalar.value {
+        ScalarType::Map(map) => {
+            for entry in map {
+                count_scalar(&entry.value, counts);
+            }
+        }
+        ScalarType::List(list) => {
+            for item in list {
+                count_scalar(item, counts);
+            }
+        }
+        leaf => {
+            let type_name = leaf.type_name();
+            match counts.iter_mut().find(|c| c.type_name == type_name) {
+                Some(count) => count.count += 1,
+                None => counts.push(TypeCount {
+                    type_name,
+                    count: 1,
+                }),
+            }
+        }
+    }
+}
+
+/// Renders the tally as a markdown section to follow the generated table
+pub fn render_type_report(counts: &[TypeCount]) -> String {
+    let tally: Vec<String> = counts.iter().map(TypeCount::to_string).collect();
+    format!("### Types\n\n{}\n", tally.join(", "))
+}
+
+/// Totals across one or more documents, written as a single machine-parseable line,
+/// `yam: 42 values, 3 undocumented, depth 4`
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Summary {
+    /// The number of leaf values
+    pub values: usize,
+    /// The number of leaf values without a description
+    pub undocumented: usize,
+    /// The deepest nesting of any document
+    pub depth: usize,
+}
+
+impl Summary {
+    /// Adds the values of another document to the totals
+    pub fn add(&mut self, document: &Document<'_>) {
+        self.add_scalar(&document.root);
+        self.depth = self.depth.max(document.depth());
+    }
+
+    fn add_scalar(&mut self, scalar: &Scalar<'_>) {
+        match &scalar.value {
+            ScalarType::Map(map) => map.iter().for_each(|entry| self.add_scalar(&entry.value)),
+            ScalarType::List(list) => list.iter().for_each(|item| self.add_scalar(item)),
+            _ => {
+                self.values += 1;
+                if scalar.comment.is_none() {
+                    self.undocumented += 1;
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "yam: {} values, {} undocumented, depth {}",
+            self.values, self.undocumented, self.depth
+        )
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+
+use crate::{
+    error::{Result, YamError},
+    parser::Document,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+pub fn validate_document(
+    document: &Document<'_>,
+    schema: &serde_json::Value,
+) -> Result<Vec<Violation>> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| YamError::Schema(Box::new(e)))?;
+
+    let instance = document.to_value();
+    let violations = validator
+        .iter_errors(&instance)
+        .map(|error| Violation {
+            path: to_dotted_path(&error.instance_path.to_string()),
+            message: error.to_string(),
+        })
+        .collect();
+
+    Ok(violations)
+}
+
+// Schema errors are located by a JSON pointer, `/image/tag`, which is rewritten
+// to match the dotted naming used within the generated tables
+fn to_dotted_path(pointer: &str) -> String {
+    pointer
+        .trim_start_matches('/')
+        .split('/')
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>()
+        .join(".")
+}
@@ -0,0 +1,47 @@
+use crate::{
+    error::Result,
+    markdown::{Flattened, RenderOptions, collapse_cell, flatten_document, push_truncation_note},
+    parser::Document,
+};
+
+/// Renders a list in Slack's mrkdwn, which has no tables, writing each value as
+/// `*name*: value` followed by its description in italics
+pub fn render_slack(document: &Document<'_>, options: &RenderOptions) -> Result<String> {
+    let mut output = String::new();
+
+    let Flattened { rows, omitted } = flatten_document(document, options);
+    for row in rows {
+        if options.number_rows {
+            output.push_str(&format!("{}. ", row.index));
+        }
+        output.push_str(&format!("*{}*:", escape(&row.name)));
+
+        let value = collapse_cell(&row.value, options);
+        if !value.is_empty() {
+            output.push_str(&format!(" {}", escape(&value).replace('\n', " ")));
+        }
+        if options.show_required && row.required {
+            output.push_str(&format!(" ({})", escape(&options.labels.required)));
+        }
+        output.push('\n');
+
+        // Italics can't span a line break, so each line of a description is wrapped
+        for line in row
+            .description
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+        {
+            output.push_str(&format!("_{}_\n", escape(line.trim())));
+        }
+    }
+
+    push_truncation_note(&mut output, omitted);
+    Ok(output)
+}
+
+// Slack reads these three characters as control sequences, such as `<@user>` mentions
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
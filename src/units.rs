@@ -0,0 +1,35 @@
+/// Describes a duration (`30s`, `2h`) or byte size (`512Mi`, `1G`) by normalizing it
+/// to seconds or bytes. Returns `None` for any value without a recognized unit suffix.
+/// A bare `m` is left alone, as it is as likely to be a Kubernetes CPU quantity,
+/// `100m`, as a number of minutes
+pub fn describe_units(value: &str) -> Option<String> {
+    let split = value.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().ok()?;
+
+    let (multiplier, base_unit) = match unit {
+        "ms" => return Some(pluralize(number, "millisecond")),
+        "s" => (1, "second"),
+        "h" => (60 * 60, "second"),
+        "d" => (24 * 60 * 60, "second"),
+        "K" | "k" => (1_000, "byte"),
+        "M" => (1_000_000, "byte"),
+        "G" => (1_000_000_000, "byte"),
+        "T" => (1_000_000_000_000, "byte"),
+        "Ki" => (1 << 10, "byte"),
+        "Mi" => (1 << 20, "byte"),
+        "Gi" => (1 << 30, "byte"),
+        "Ti" => (1 << 40, "byte"),
+        _ => return None,
+    };
+
+    Some(pluralize(number.checked_mul(multiplier)?, base_unit))
+}
+
+fn pluralize(count: u64, unit: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, unit)
+    } else {
+        format!("{} {}s", count, unit)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{
+    error::Result,
+    markdown::format_scalar_value,
+    parser::{Document, Scalar, ScalarType},
+};
+
+/// Writes the document as XML. Map keys become element names, list entries become
+/// repeated `<item>` elements and every element carries a `type` attribute
+pub fn render_xml(document: &Document<'_>) -> Result<String> {
+    let mut output = String::new();
+    write_element(&mut output, "document", &document.root, 0);
+    Ok(output)
+}
+
+fn write_element(output: &mut String, tag: &str, scalar: &Scalar<'_>, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let value_type = scalar
+        .explicit_type
+        .as_deref()
+        .unwrap_or_else(|| scalar.value.type_name());
+    let open = format!("{}<{} type=\"{}\"", indent, tag, escape_text(value_type));
+
+    match &scalar.value {
+        ScalarType::Map(map) if !map.is_empty() => {
+            output.push_str(&format!("{}>\n", open));
+            for entry in map {
+                write_element(output, &sanitize_tag(entry.key), &entry.value, depth + 1);
+            }
+            output.push_str(&format!("{}</{}>\n", indent, tag));
+        }
+        ScalarType::List(list) if !list.is_empty() => {
+            output.push_str(&format!("{}>\n", open));
+            for item in list {
+                write_element(output, "item", item, depth + 1);
+            }
+            output.push_str(&format!("{}</{}>\n", indent, tag));
+        }
+        ScalarType::Map(_) | ScalarType::List(_) | ScalarType::Null => {
+            output.push_str(&format!("{}/>\n", open));
+        }
+        leaf => {
+            output.push_str(&format!(
+                "{}>{}</{}>\n",
+                open,
+                escape_text(&format_scalar_value(leaf)),
+                tag
+            ));
+        }
+    }
+}
+
+// Characters that cannot appear within an element name are replaced with `_`, and
+// names that cannot start an element, such as `8080`, are prefixed with one. Colons
+// are replaced too, as they would otherwise declare a namespace
+fn sanitize_tag(key: &str) -> String {
+    let mut tag: String = key
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if !tag.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        tag.insert(0, '_');
+    }
+    tag
+}
+
+// XML 1.0 can't hold most control characters, even escaped, so those are replaced
+// with U+FFFD to keep the document well-formed
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            '\0'..='\x1f' | '\u{fffe}' | '\u{ffff}' => escaped.push('\u{fffd}'),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,167 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::{
+    error::Result,
+    markdown::format_scalar_value,
+    parser::{Document, Scalar, ScalarType, TIMESTAMP},
+};
+
+// Plain scalars that would be read back as a null, boolean or number under the core
+// schema, and so must be quoted to remain strings
+static NON_STRING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(~|null|Null|NULL|true|True|TRUE|false|False|FALSE|[-+]?[0-9]+|0o[0-7]+|0x[0-9a-fA-F]+|[-+]?(\.[0-9]+|[0-9]+(\.[0-9]*)?)([eE][-+]?[0-9]+)?|[-+]?\.(inf|Inf|INF)|\.(nan|NaN|NAN))$",
+    )
+    .expect("core schema pattern should compile")
+});
+
+/// Re-emits the document as canonical YAML: block style with two space indentation,
+/// strings quoted only when they must be, and each description written as a comment
+/// above its value. Aliases are written out in full
+pub fn render_yaml(document: &Document<'_>) -> Result<String> {
+    let mut output = String::new();
+    let root = &document.root;
+    // A comment at the top of the file describes both the root and its first entry,
+    // so it is written once, with that entry
+    if is_block(root) {
+        write_block(&mut output, root, 0, None);
+    } else {
+        push_comments(&mut output, root, 0, None);
+        output.push_str(&inline_value(&root.value));
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+// Writes the entries of a non-empty map or list, each line indented by `indent`.
+// Entries of a flow collection share the comment of the collection itself, which
+// is `inherited` and isn't repeated for each of them
+fn write_block(output: &mut String, scalar: &Scalar<'_>, indent: usize, inherited: Option<&str>) {
+    let padding = " ".repeat(indent);
+    match &scalar.value {
+        ScalarType::Map(map) => {
+            for entry in map {
+                push_comments(output, &entry.value, indent, inherited);
+                output.push_str(&format!("{}{}:", padding, quote(entry.key)));
+                if is_block(&entry.value) {
+                    output.push('\n');
+                    let comment = entry.value.comment.as_deref();
+                    write_block(output, &entry.value, indent + 2, comment);
+                } else {
+                    output.push_str(&format!(" {}\n", inline_value(&entry.value.value)));
+                }
+            }
+        }
+        ScalarType::List(list) => {
+            for item in list {
+                push_comments(output, item, indent, inherited);
+                if is_block(item) {
+                    output.push_str(&format!("{}-", padding));
+                    let mut nested = String::new();
+                    write_block(&mut nested, item, indent + 2, item.comment.as_deref());
+
+                    // The first line of a nested block shares the line of its dash, unless
+                    // it is a comment that would swallow the value
+                    let first_line = &nested[indent + 2..];
+                    if first_line.starts_with('#') {
+                        output.push('\n');
+                        output.push_str(&nested);
+                    } else {
+                        output.push(' ');
+                        output.push_str(first_line);
+                    }
+                } else {
+                    output.push_str(&format!("{}- {}\n", padding, inline_value(&item.value)));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_block(scalar: &Scalar<'_>) -> bool {
+    match &scalar.value {
+        ScalarType::Map(map) => !map.is_empty(),
+        ScalarType::List(list) => !list.is_empty(),
+        _ => false,
+    }
+}
+
+// Annotations are written back in the form they are parsed from, after the description
+fn push_comments(output: &mut String, scalar: &Scalar<'_>, indent: usize, inherited: Option<&str>) {
+    let padding = " ".repeat(indent);
+    if let Some(comment) = scalar.comment.as_deref().filter(|&c| Some(c) != inherited) {
+        for line in comment.lines() {
+            if line.is_empty() {
+                output.push_str(&format!("{}#\n", padding));
+            } else {
+                output.push_str(&format!("{}# {}\n", padding, line));
+            }
+        }
+    }
+    if let Some(default) = &scalar.explicit_default {
+        output.push_str(&format!("{}# @default -- {}\n", padding, default));
+    }
+    if let Some(value_type) = &scalar.explicit_type {
+        output.push_str(&format!("{}# @type -- {}\n", padding, value_type));
+    }
+    if scalar.required {
+        output.push_str(&format!("{}# @required\n", padding));
+    }
+}
+
+fn inline_value(value: &ScalarType<'_>) -> String {
+    match value {
+        ScalarType::String(s) => quote(s),
+        ScalarType::Text(s) => quote(s),
+        ScalarType::Float(n) if n.is_nan() => ".nan".to_string(),
+        ScalarType::Float(n) if n.is_infinite() => if n.is_sign_negative() {
+            "-.inf"
+        } else {
+            ".inf"
+        }
+        .to_string(),
+        // Debug formatting always keeps a decimal point or exponent, so that a
+        // whole number such as 1.0 isn't read back as an integer
+        ScalarType::Float(n) => format!("{:?}", n),
+        ScalarType::Map(_) => "{}".to_string(),
+        ScalarType::List(_) => "[]".to_string(),
+        leaf => format_scalar_value(leaf),
+    }
+}
+
+/// Writes a string as a plain scalar when it would be read back unchanged, otherwise
+/// double quoted with any special characters escaped
+fn quote(text: &str) -> String {
+    if !needs_quotes(text) {
+        return text.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn needs_quotes(text: &str) -> bool {
+    text.is_empty()
+        || text.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c) || c.is_whitespace())
+        || text.ends_with(|c: char| c == ':' || c.is_whitespace())
+        || text.contains(": ")
+        || text.contains(" #")
+        || text.contains(char::is_control)
+        || NON_STRING.is_match(text)
+        || TIMESTAMP.is_match(text)
+}
@@ -0,0 +1,479 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{self, Command, Output, Stdio},
+};
+
+fn run_yam(args: &[&str], stdin: &str) -> Output {
+    run_yam_with_env(args, stdin, &[])
+}
+
+fn run_yam_with_env(args: &[&str], stdin: &str, vars: &[(&str, &str)]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_yam"))
+        .args(args)
+        .envs(vars.iter().copied())
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn yam");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().expect("failed to wait on yam")
+}
+
+/// A fixture written to the temporary directory, removed again once dropped. The
+/// process id keeps concurrent runs of the suite apart
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, contents: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("yam-{}-{}", process::id(), name));
+        fs::write(&path, contents).unwrap();
+        TempFile(path)
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn cli_lint_writes_warnings_to_stderr() {
+    let output = run_yam(&["--lint"], "? bare\n");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("warning: bare"));
+}
+
+#[test]
+fn cli_quiet_suppresses_warnings() {
+    let output = run_yam(&["--lint", "--quiet"], "? bare\n");
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("| bare | null |  |"));
+}
+
+#[test]
+fn cli_multi_document_headings() {
+    let output = run_yam(
+        &[],
+        "kind: ConfigMap\nmetadata:\n  name: settings\n---\nreplicas: 3\n",
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let config_map = stdout.find("## ConfigMap/settings").unwrap();
+    let second = stdout.find("## Document 2").unwrap();
+    assert!(config_map < stdout.find("| kind | ConfigMap |").unwrap());
+    assert!(second < stdout.find("| replicas | 3 |").unwrap());
+}
+
+#[test]
+fn cli_multi_document_separated_by_rule() {
+    let output = run_yam(&[], "a: 1\n---\nb: 2\n");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("| a | 1 |  |\n\n---\n\n## Document 2\n"));
+    assert!(!stdout.starts_with("---"));
+}
+
+#[test]
+fn cli_input_format_env() {
+    let output = run_yam(
+        &["--input-format", "env"],
+        "# the port to listen on\nPORT=8080\nNAME=api\n",
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("| PORT | 8080 | the port to listen on |"));
+    assert!(stdout.contains("| NAME | api |  |"));
+}
+
+#[test]
+fn cli_list_layout_requires_markdown() {
+    let output = run_yam(&["--layout", "list", "--format", "grid"], "name: api\n");
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--layout list is only supported by the markdown format")
+    );
+}
+
+#[test]
+fn cli_entry_counts_list_indices_from_index_base() {
+    let yaml = "ports:\n  - name: http\n  - name: https\n";
+
+    let output = run_yam(&["--entry", "ports.1", "--index-base", "1"], yaml);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("| name | http |"));
+
+    let output = run_yam(&["--entry", "ports.1"], yaml);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("| name | https |"));
+
+    let output = run_yam(&["--entry", "ports.0", "--index-base", "1"], yaml);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn cli_report_requires_a_markdown_format() {
+    let output = run_yam(&["--report", "types", "--format", "xml"], "name: api\n");
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--report is only supported by the markdown formats")
+    );
+
+    let output = run_yam(
+        &["--report", "types", "--format", "markdown-minimal"],
+        "name: api\n",
+    );
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("### Types"));
+}
+
+#[test]
+fn cli_max_rows_limits_list_layout() {
+    let output = run_yam(
+        &["--layout", "list", "--max-rows", "2"],
+        "a: 1\nb:\n  c: 2\n  d: 3\ne: 4\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "- a: 1\n- b\n  - c: 2\n\n(truncated, 2 rows omitted)\n\n"
+    );
+}
+
+#[test]
+fn cli_max_rows_rejected_with_rows_json() {
+    let output = run_yam(&["--emit-rows-json", "--max-rows", "1"], "a: 1\nb: 2\n");
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--max-rows is not supported with --emit-rows-json")
+    );
+}
+
+#[test]
+fn cli_hide_internal_applies_to_every_format() {
+    let yaml = "# @internal\ntoken: 1\nname: api\nsecret: 2 # @internal\n";
+
+    let output = run_yam(&["--hide-internal", "--format", "yaml"], yaml);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "name: api\n\n");
+
+    let output = run_yam(&["--hide-internal", "--format", "xml"], yaml);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "<document type=\"map\">\n  <name type=\"string\">api</name>\n</document>\n\n"
+    );
+
+    let output = run_yam(&["--hide-internal"], yaml);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("| name | api |  |"));
+    assert!(!stdout.contains("token") && !stdout.contains("secret"));
+}
+
+#[test]
+fn cli_emit_rows_json_writes_a_single_json_value() {
+    let output = run_yam(
+        &["--emit-rows-json", "--title", "Settings"],
+        "a: 1\n---\nb: 2\n",
+    );
+    assert!(output.status.success());
+
+    let documents: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<Vec<&str>> = documents
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|rows| {
+            rows.as_array()
+                .unwrap()
+                .iter()
+                .map(|row| row["name"].as_str().unwrap())
+                .collect()
+        })
+        .collect();
+    assert_eq!(names, [["a"], ["b"]]);
+
+    let output = run_yam(&["--emit-rows-json", "--title", "Settings"], "a: 1\n");
+    let rows: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(rows[0]["name"], "a");
+}
+
+#[test]
+fn cli_levels_rejects_zero() {
+    let output = run_yam(&["--levels", "0"], "name: api\n");
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("invalid value '0' for '--levels <N>'")
+    );
+
+    let output = run_yam(&["--levels", "1"], "name: api\n");
+    assert!(output.status.success());
+}
+
+#[test]
+fn cli_input_format_frontmatter() {
+    let output = run_yam(
+        &["--input-format", "frontmatter"],
+        "---\ntitle: Hello\n---\n# Heading\n\nSome markdown\n",
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("| title | Hello |  |"));
+    assert!(!stdout.contains("Heading"));
+}
+
+#[test]
+fn cli_entry_documents_subtree_with_relative_paths() {
+    let yaml = "ingress:\n  tls:\n    secret: tls-cert\n    hosts: [example.com]\nreplicas: 3\n";
+    let output = run_yam(&["--entry", "ingress.tls"], yaml);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("| secret | tls-cert |  |"));
+    assert!(stdout.contains("| hosts.0 | example.com |  |"));
+    assert!(!stdout.contains("replicas"));
+}
+
+#[test]
+fn cli_entry_missing_path() {
+    let output = run_yam(&["--entry", "ingress.missing"], "ingress:\n  tls: {}\n");
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("entry path not found: ingress.missing")
+    );
+}
+
+#[test]
+fn cli_max_rows_truncates_table() {
+    let yaml: String = (0..50).map(|n| format!("key{n}: {n}\n")).collect();
+    let output = run_yam(&["--max-rows", "3"], &yaml);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.starts_with("| key"))
+        .collect();
+    assert_eq!(
+        rows,
+        ["| key0 | 0 |  |", "| key1 | 1 |  |", "| key2 | 2 |  |"]
+    );
+    assert!(stdout.contains("(truncated, 47 rows omitted)"));
+}
+
+#[test]
+fn cli_max_rows_applies_after_sorting() {
+    let output = run_yam(&["--sort", "keys", "--max-rows", "1"], "c: 1\nb: 2\na: 3\n");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("| a | 3 |  |"));
+    assert!(!stdout.contains("| c | 1 |"));
+    assert!(stdout.contains("(truncated, 2 rows omitted)"));
+}
+
+#[test]
+fn cli_max_rows_counts_nested_rows_left_out() {
+    let output = run_yam(
+        &["--max-rows", "1", "--anchors"],
+        "a: 1\nb:\n  c: [1, 2]\n  d: {}\n",
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("(truncated, 3 rows omitted)"));
+}
+
+#[test]
+fn cli_color_always_colors_warnings_written_to_a_pipe() {
+    let output = run_yam(&["--lint", "--color", "always"], "? bare\n");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("\x1b[33mwarning\x1b[0m: bare"));
+}
+
+#[test]
+fn cli_color_never_writes_no_escapes() {
+    let output = run_yam(&["--lint", "--color", "never"], "? bare\n");
+    assert!(output.status.success());
+    assert!(!output.stderr.contains(&0x1b));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("warning: bare"));
+}
+
+#[test]
+fn cli_clicolor_force_colors_warnings_written_to_a_pipe() {
+    // An empty NO_COLOR is ignored, which keeps one set by the caller from leaking in
+    let vars = [("CLICOLOR_FORCE", "1"), ("NO_COLOR", "")];
+    let output = run_yam_with_env(&["--lint"], "? bare\n", &vars);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("\x1b[33mwarning\x1b[0m: bare"));
+
+    let vars = [("CLICOLOR_FORCE", "0"), ("NO_COLOR", "")];
+    let output = run_yam_with_env(&["--lint"], "? bare\n", &vars);
+    assert!(!output.stderr.contains(&0x1b));
+}
+
+#[test]
+fn cli_no_color_wins_over_clicolor_force() {
+    let vars = [("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")];
+    let output = run_yam_with_env(&["--lint"], "? bare\n", &vars);
+    assert!(output.status.success());
+    assert!(!output.stderr.contains(&0x1b));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("warning: bare"));
+
+    let output = run_yam_with_env(&["--lint", "--color", "always"], "? bare\n", &vars);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("\x1b[33mwarning\x1b[0m: bare"));
+}
+
+#[test]
+fn cli_title_precedes_table() {
+    let output = run_yam(
+        &["--title", "Configuration"],
+        "name: truman\n---\nage: 42\n",
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("**Configuration**\n"));
+    assert_eq!(stdout.matches("**Configuration**").count(), 1);
+    assert!(stdout.find("**Configuration**") < stdout.find("| Name | Value |"));
+}
+
+#[test]
+fn cli_summary_line_written_to_stderr() {
+    let output = run_yam(&["--summary-line"], "# the name\nname: api\nport: 80\n");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "yam: 2 values, 1 undocumented, depth 1\n"
+    );
+}
+
+#[test]
+fn cli_template_from_environment() {
+    let template = "{% for row in rows %}{{ row.name }}={{ row.value }}\n{% endfor %}";
+    let output = run_yam_with_env(
+        &[],
+        "name: truman\nage: 42\n",
+        &[("YAM_TEMPLATE", template)],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "name=truman\nage=42\n\n"
+    );
+}
+
+#[test]
+fn cli_template_flag_takes_precedence_over_environment() {
+    let file = TempFile::new(
+        "template.tera",
+        "{% for row in rows %}[{{ row.name }}]{% endfor %}",
+    );
+
+    let output = run_yam_with_env(
+        &["--template", file.path()],
+        "name: truman\n",
+        &[("YAM_TEMPLATE", "ignored")],
+    );
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "[name]\n");
+}
+
+#[test]
+fn cli_sort_maps_sorts_nested_keys_in_json_rows() {
+    let output = run_yam(
+        &["--sort-maps", "--emit-rows-json"],
+        "service:\n  port: 80\n  name: api\nimage: nginx\n",
+    );
+    assert!(output.status.success());
+
+    let rows: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<&str> = rows
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["image", "service.name", "service.port"]);
+}
+
+#[test]
+fn cli_locale_renders_translated_labels() {
+    let file = TempFile::new(
+        "locale.yaml",
+        "name: Name\nvalue: Wert\ndescription: Beschreibung\nrequired: Pflicht\nyes: Ja\nno: Nein\ntrue: wahr\nfalse: falsch\nnull: leer\n",
+    );
+
+    let output = run_yam(
+        &["--locale", file.path(), "--show-required"],
+        "# @required\nenabled: true\ndebug: false\nproxy:\n",
+    );
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("| Name | Wert | Pflicht | Beschreibung |"));
+    assert!(stdout.contains("| enabled | wahr | Ja |  |"));
+    assert!(stdout.contains("| debug | falsch | Nein |  |"));
+    assert!(stdout.contains("| proxy | leer | Nein |  |"));
+}
+
+#[test]
+fn cli_dry_run_writes_no_output() {
+    let output = run_yam(
+        &["--dry-run", "--lint", "--fail-on-warnings"],
+        "name: api\n",
+    );
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "yam: 1 values, 1 undocumented, depth 1\n"
+    );
+}
+
+#[test]
+fn cli_dry_run_fails_on_warnings() {
+    let output = run_yam(&["--dry-run", "--lint", "--fail-on-warnings"], "? bare\n");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning: bare"));
+    assert!(stderr.contains("1 warning(s) reported"));
+}
+
+#[test]
+fn cli_markdown_table_only_without_surrounding_blank_lines() {
+    let output = run_yam(
+        &["--format", "markdown-table-only", "--no-trailing-newline"],
+        "name: truman\nage: 42\n",
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "| Name | Value | Description |\n|------|-------|-------------|\n| name | truman |  |\n| age | 42 |  |"
+    );
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "cli")]
+
+use anyhow::{Ok, Result};
+use yam::{confluence::render_confluence, markdown::RenderOptions, parser::parse};
+
+#[test]
+fn render_confluence_table() -> Result<()> {
+    let yaml = r#"
+        # the name of the user
+        name: truman
+        pipe: a|b
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let output = render_confluence(&document, &RenderOptions::default())?;
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "||Name||Value||Description||");
+    assert_eq!(lines[1], "|name|truman|the name of the user|");
+    assert_eq!(lines[2], "|pipe|a\\|b| |");
+
+    Ok(())
+}
+
+#[test]
+fn render_confluence_keeps_indentation_of_literal_block() -> Result<()> {
+    let yaml = "script: |\n  if x:\n    run()\n  done\n";
+
+    let document = parse(yaml)?.unwrap();
+    let output = render_confluence(&document, &RenderOptions::default())?;
+
+    assert!(output.contains("|script|if x:\\\\  run()\\\\done| |"));
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anyhow::{Ok, Result};
+use yam::{
+    explode::explode_keys,
+    markdown::{RenderOptions, render_markdown},
+    parser::parse,
+};
+
+#[test]
+fn explode_keys_matches_nested_form() -> Result<()> {
+    let dotted = r#"
+        # the port to listen on
+        server.http.port: 8080
+        server.http.host: localhost
+        server:
+          name: api
+        "#;
+    let nested = r#"
+        server:
+          http:
+            # the port to listen on
+            port: 8080
+            host: localhost
+          name: api
+        "#;
+
+    let exploded = explode_keys(&parse(dotted)?.unwrap())?;
+    let options = RenderOptions::default();
+    assert_eq!(
+        render_markdown(&exploded, &options)?,
+        render_markdown(&parse(nested)?.unwrap(), &options)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn explode_keys_conflicting_values() -> Result<()> {
+    let document = parse("a.b: 1\na.b.c: 2")?.unwrap();
+    let error = explode_keys(&document).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "conflicting values for key a.b after exploding dotted keys"
+    );
+
+    Ok(())
+}
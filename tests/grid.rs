@@ -0,0 +1,64 @@
+#![cfg(feature = "cli")]
+
+use anyhow::{Ok, Result};
+use yam::{grid::render_grid, markdown::RenderOptions, parser::parse};
+
+#[test]
+fn render_grid_table() -> Result<()> {
+    let yaml = r#"
+        # the name of the user
+        name: truman
+        age: 30
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let output = render_grid(&document, &RenderOptions::default())?;
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "┌──────┬────────┬──────────────────────┐");
+    assert_eq!(lines[1], "│ Name │ Value  │ Description          │");
+    assert_eq!(lines[2], "├──────┼────────┼──────────────────────┤");
+    assert_eq!(lines[3], "│ name │ truman │ the name of the user │");
+    assert_eq!(lines[4], "│ age  │ 30     │                      │");
+    assert_eq!(lines[5], "└──────┴────────┴──────────────────────┘");
+
+    Ok(())
+}
+
+#[test]
+fn render_grid_pads_wide_characters_by_display_width() -> Result<()> {
+    let yaml = r#"
+        # 名前
+        name: 東京
+        mood: 🚀
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let output = render_grid(&document, &RenderOptions::default())?;
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "┌──────┬───────┬─────────────┐");
+    assert_eq!(lines[3], "│ name │ 東京  │ 名前        │");
+    assert_eq!(lines[4], "│ mood │ 🚀    │             │");
+
+    Ok(())
+}
+
+#[test]
+fn render_grid_ascii() -> Result<()> {
+    let document = parse("name: truman")?.unwrap();
+
+    let options = RenderOptions {
+        ascii: true,
+        ..Default::default()
+    };
+    let output = render_grid(&document, &options)?;
+
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "+------+--------+-------------+");
+    assert_eq!(lines[1], "| Name | Value  | Description |");
+    assert_eq!(lines[3], "| name | truman |             |");
+    assert_eq!(lines[4], "+------+--------+-------------+");
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+
+use anyhow::{Ok, Result};
+use flate2::{Compression, write::GzEncoder};
+use yam::input::{
+    Encoding, InputFormat, decode_input, decode_input_with_encoding, extract_front_matter,
+};
+
+#[test]
+fn input_format_from_path() {
+    assert_eq!(
+        InputFormat::from_path("README.md"),
+        InputFormat::Frontmatter
+    );
+    assert_eq!(
+        InputFormat::from_path("values.yaml.gz"),
+        InputFormat::GzipYaml
+    );
+    assert_eq!(InputFormat::from_path(".env"), InputFormat::Env);
+    assert_eq!(
+        InputFormat::from_path("config/.env.local"),
+        InputFormat::Env
+    );
+    assert_eq!(InputFormat::from_path("values"), InputFormat::Yaml);
+}
+
+#[test]
+fn extract_front_matter_markers() {
+    assert_eq!(
+        extract_front_matter("---\ntitle: Hello\n...\n"),
+        Some("title: Hello\n")
+    );
+    assert_eq!(extract_front_matter("---\ntitle: Hello\n"), None);
+    assert_eq!(extract_front_matter("title: Hello\n"), None);
+}
+
+#[test]
+fn decode_input_gzip_yaml() -> Result<()> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(b"name: truman\n")?;
+    let bytes = encoder.finish()?;
+
+    assert_eq!(
+        decode_input(bytes, InputFormat::GzipYaml)?,
+        "name: truman\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn decode_input_transcodes_latin1() -> Result<()> {
+    let bytes = b"caf\xe9: ouvert\n".to_vec();
+    let text = decode_input_with_encoding(bytes, InputFormat::Yaml, Encoding::Latin1)?;
+    assert_eq!(text, "café: ouvert\n");
+
+    Ok(())
+}
+
+#[test]
+fn decode_input_transcodes_windows_1252() -> Result<()> {
+    let bytes = b"price: \x8010 \x96 caf\xe9\n".to_vec();
+    let text = decode_input_with_encoding(bytes, InputFormat::Yaml, Encoding::Windows1252)?;
+    assert_eq!(text, "price: €10 – café\n");
+
+    Ok(())
+}
+
+#[test]
+fn decode_input_rejects_latin1_as_utf8() {
+    assert!(decode_input(b"caf\xe9: ouvert\n".to_vec(), InputFormat::Yaml).is_err());
+}
+
+#[test]
+fn decode_input_env() -> Result<()> {
+    let env = "# the port to listen on\nPORT=8080\n\nexport NAME='my app' # shown in logs\nGREETING=\"hi\\tthere\"\nQUOTED=\"say \\\"hi\\\"\"\nPATH_LIKE=C:\\bin\n";
+
+    assert_eq!(
+        decode_input(env.as_bytes().to_vec(), InputFormat::Env)?,
+        "# the port to listen on\nPORT: \"8080\"\n\nNAME: \"my app\" # shown in logs\nGREETING: \"hi\\tthere\"\nQUOTED: \"say \\\"hi\\\"\"\nPATH_LIKE: \"C:\\\\bin\"\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn decode_input_env_invalid_entry() {
+    let result = decode_input(b"PORT=8080\nnot an entry\n".to_vec(), InputFormat::Env);
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid env entry at line 2")
+    );
+}
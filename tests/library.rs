@@ -0,0 +1,32 @@
+use anyhow::{Ok, Result};
+use yam::{
+    markdown::{RenderOptions, document_markdown, render_markdown},
+    parser::parse,
+};
+
+// Run with `cargo test --no-default-features` to check the library is usable
+// without the cli feature
+#[test]
+fn library_parses_and_renders_without_cli() -> Result<()> {
+    let document = parse("# The number of replicas\nreplicas: 3\n")?.unwrap();
+
+    let output = render_markdown(&document, &RenderOptions::default())?;
+    assert!(output.contains("| replicas | 3 | The number of replicas |"));
+
+    Ok(())
+}
+
+#[test]
+fn library_document_markdown_matches_parse_then_render() -> Result<()> {
+    let yaml = "# The number of replicas\nreplicas: 3\nimage:\n  tag: latest\n";
+    let options = RenderOptions::default();
+
+    let document = parse(yaml)?.unwrap();
+    assert_eq!(
+        document_markdown(yaml, &options)?,
+        Some(render_markdown(&document, &options)?)
+    );
+    assert_eq!(document_markdown("", &options)?, None);
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anyhow::{Ok, Result};
+use yam::{lint::lint_document, parser::*};
+
+#[test]
+fn parse_block_mapping_explicit_empty_value_and_bare_key() -> Result<()> {
+    let yaml = r#"
+        empty:
+        ? bare
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    match document.root.value {
+        ScalarType::Map(ref map) => {
+            assert_eq!(map.len(), 2);
+            assert_eq!(map[0].key, "empty");
+            assert_eq!(map[0].value.value, ScalarType::Null);
+            assert!(!map[0].bare_key);
+            assert_eq!(map[1].key, "bare");
+            assert_eq!(map[1].value.value, ScalarType::Null);
+            assert!(map[1].bare_key);
+        }
+        _ => panic!("root node should contain a map scalar"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lint_document_warns_on_bare_key() -> Result<()> {
+    let yaml = r#"
+        config:
+          empty:
+          ? bare
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let lints = lint_document(&document);
+
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].path, "config.bare");
+
+    Ok(())
+}
+
+#[test]
+fn lint_document_warns_on_redefined_anchor() -> Result<()> {
+    let yaml = "first: &x 1\nsecond: &x 2\nthird: *x\n";
+
+    let document = parse(yaml)?.unwrap();
+    assert_eq!(
+        document.root.get("third").unwrap().value,
+        ScalarType::Integer(2)
+    );
+
+    let lints = lint_document(&document);
+    assert_eq!(lints.len(), 1);
+    assert_eq!(lints[0].path, "&x");
+
+    Ok(())
+}
+
+#[test]
+fn parse_strict_anchors_rejects_redefined_anchor() {
+    let options = ParseOptions {
+        strict_anchors: true,
+        ..Default::default()
+    };
+    let error = parse_with_options("first: &x 1\nsecond: &x 2\n", &options).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("duplicate anchor &x at line 2, column 9")
+    );
+}
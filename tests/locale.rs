@@ -0,0 +1,20 @@
+use anyhow::{Ok, Result};
+use yam::{error::YamError, locale::Labels};
+
+#[test]
+fn labels_from_yaml_keeps_defaults_for_missing_labels() -> Result<()> {
+    let labels = Labels::from_yaml("description: Beschreibung\ntrue: ja")?;
+
+    assert_eq!(labels.description, "Beschreibung");
+    assert_eq!(labels.true_value, "ja");
+    assert_eq!(labels.name, "Name");
+    assert_eq!(labels.false_value, "false");
+
+    Ok(())
+}
+
+#[test]
+fn labels_from_yaml_rejects_unknown_label() {
+    let error = Labels::from_yaml("descripton: Beschreibung").unwrap_err();
+    assert!(matches!(error, YamError::Locale(message) if message == "unknown label descripton"));
+}
@@ -0,0 +1,929 @@
+use anyhow::{Ok, Result};
+use yam::{
+    error::YamError,
+    markdown::{
+        EmptyContainer, IndexStyle, Layout, RenderOptions, SortOrder, TableRow, document_heading,
+        render_markdown, render_markdown_minimal, render_markdown_stream,
+        render_markdown_with_hook, render_rows_json,
+    },
+    parser::{ParseOptions, parse, parse_all, parse_with_options},
+};
+
+#[test]
+fn render_markdown_with_number_rows() -> Result<()> {
+    let yaml = r#"
+        name: truman
+        age: 42
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let options = RenderOptions {
+        number_rows: true,
+        ..Default::default()
+    };
+    let markdown = render_markdown(&document, &options)?;
+
+    let lines: Vec<&str> = markdown.lines().filter(|l| !l.is_empty()).collect();
+    assert_eq!(lines[0], "| # | Name | Value | Description |");
+    assert_eq!(lines[2], "| 1 | name | truman |  |");
+    assert_eq!(lines[3], "| 2 | age | 42 |  |");
+
+    Ok(())
+}
+
+fn render_with_empty_container(mode: EmptyContainer) -> Result<String> {
+    let document = parse("name: truman\ntags: []")?.unwrap();
+    let options = RenderOptions {
+        empty_container: mode,
+        ..Default::default()
+    };
+    Ok(render_markdown(&document, &options)?)
+}
+
+#[test]
+fn render_markdown_empty_container_as_brackets() -> Result<()> {
+    let markdown = render_with_empty_container(EmptyContainer::Brackets)?;
+    assert!(markdown.contains("| tags | [] |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_empty_container_as_null() -> Result<()> {
+    let markdown = render_with_empty_container(EmptyContainer::Null)?;
+    assert!(markdown.contains("| tags | null |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_empty_container_omitted() -> Result<()> {
+    let markdown = render_with_empty_container(EmptyContainer::Omit)?;
+    assert!(markdown.contains("| name | truman |  |"));
+    assert!(!markdown.contains("tags"));
+
+    Ok(())
+}
+
+fn render_rows_with_sort(sort: SortOrder) -> Result<Vec<String>> {
+    let yaml = r#"
+        name: bob
+        enabled: true
+        age: 42
+        city: amsterdam
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let options = RenderOptions {
+        sort,
+        ..Default::default()
+    };
+    let markdown = render_markdown(&document, &options)?;
+
+    Ok(markdown
+        .lines()
+        .skip(3)
+        .map(|line| line.split('|').nth(1).unwrap().trim().to_string())
+        .collect())
+}
+
+#[test]
+fn render_markdown_sorted_by_keys() -> Result<()> {
+    let names = render_rows_with_sort(SortOrder::Keys)?;
+    assert_eq!(names, vec!["age", "city", "enabled", "name"]);
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_sorted_by_values() -> Result<()> {
+    let names = render_rows_with_sort(SortOrder::Values)?;
+    assert_eq!(names, vec!["age", "city", "name", "enabled"]);
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_sorted_by_types() -> Result<()> {
+    let names = render_rows_with_sort(SortOrder::Types)?;
+    assert_eq!(names, vec!["enabled", "age", "name", "city"]);
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_list_layout() -> Result<()> {
+    let yaml = r#"
+        image:
+          # the registry to pull from
+          registry: docker.io
+          tag: latest
+        replicas: 3
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let options = RenderOptions {
+        layout: Layout::List,
+        ..Default::default()
+    };
+    let markdown = render_markdown(&document, &options)?;
+
+    assert_eq!(
+        markdown,
+        r#"- image
+  - registry: docker.io — the registry to pull from
+  - tag: latest
+- replicas: 3
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_bracket_index_style() -> Result<()> {
+    let yaml = r#"
+        ports:
+          80: http
+          443: https
+          extra:
+            - 8080
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let options = RenderOptions {
+        index_style: IndexStyle::Bracket,
+        ..Default::default()
+    };
+    let markdown = render_markdown(&document, &options)?;
+
+    assert!(markdown.contains("| ports.80 | http |  |"));
+    assert!(markdown.contains("| ports.443 | https |  |"));
+    assert!(markdown.contains("| ports.extra[0] | 8080 |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_one_based_index() -> Result<()> {
+    let document = parse("items: [a, b, c]")?.unwrap();
+    let options = RenderOptions {
+        index_base: 1,
+        ..Default::default()
+    };
+    let markdown = render_markdown(&document, &options)?;
+
+    assert!(markdown.contains("| items.1 | a |  |"));
+    assert!(markdown.contains("| items.2 | b |  |"));
+    assert!(markdown.contains("| items.3 | c |  |"));
+    assert!(!markdown.contains("items.0"));
+
+    let options = RenderOptions {
+        index_style: IndexStyle::Bracket,
+        ..options
+    };
+    assert!(render_markdown(&document, &options)?.contains("| items[3] | c |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_prefers_default_annotation() -> Result<()> {
+    let yaml = r#"
+        # @default -- generated at install time
+        password:
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let markdown = render_markdown(&document, &RenderOptions::default())?;
+    assert!(markdown.contains("| password | generated at install time |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_minimal_has_two_columns() -> Result<()> {
+    let document = parse("name: truman # the name")?.unwrap();
+    let markdown = render_markdown_minimal(&document, &RenderOptions::default())?;
+
+    let lines: Vec<&str> = markdown.lines().filter(|l| !l.is_empty()).collect();
+    assert_eq!(lines[0], "| Name | Value |");
+    assert_eq!(lines[1], "|------|-------|");
+    assert_eq!(lines[2], "| name | truman |");
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_annotate_units() -> Result<()> {
+    let yaml = r#"
+        timeout: 30s
+        memory: 512Mi
+        cpu: 100m
+        name: 30 seconds
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let options = RenderOptions {
+        annotate_units: true,
+        ..Default::default()
+    };
+    let markdown = render_markdown(&document, &options)?;
+
+    assert!(markdown.contains("| timeout | 30s (30 seconds) |  |"));
+    assert!(markdown.contains("| memory | 512Mi (536870912 bytes) |  |"));
+    assert!(markdown.contains("| cpu | 100m |  |"));
+    assert!(markdown.contains("| name | 30 seconds |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_preserve_comment_breaks() -> Result<()> {
+    let yaml = r#"
+        # the first line
+        # the second line
+        name: truman
+        "#;
+
+    let parse_options = ParseOptions {
+        preserve_comment_breaks: true,
+        ..Default::default()
+    };
+    let document = parse_with_options(yaml, &parse_options)?.unwrap();
+    let markdown = render_markdown(&document, &RenderOptions::default())?;
+
+    assert!(markdown.contains("| name | truman | the first line<br>the second line |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_rows_json_matches_flattened_rows() -> Result<()> {
+    let yaml = r#"
+        # The name of the service
+        name: api
+        ports:
+          - 80
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let json = render_rows_json(&document, &RenderOptions::default())?;
+    let rows: serde_json::Value = serde_json::from_str(&json)?;
+
+    assert_eq!(
+        rows,
+        serde_json::json!([
+            {
+                "index": 1,
+                "name": "name",
+                "value": "api",
+                "description": "The name of the service",
+                "value_type": "string",
+                "required": false
+            },
+            {
+                "index": 2,
+                "name": "ports.0",
+                "value": "80",
+                "description": "",
+                "value_type": "integer",
+                "required": false
+            }
+        ])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn document_heading_uses_kind_and_name() -> Result<()> {
+    let yaml = r#"
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: my-app
+---
+apiVersion: v1
+kind: Service
+metadata:
+  name: my-app-svc
+---
+replicas: 3
+"#;
+    let documents = parse_all(yaml)?;
+
+    let headings: Vec<String> = documents
+        .iter()
+        .enumerate()
+        .map(|(index, document)| document_heading(document, index + 1))
+        .collect();
+    assert_eq!(
+        headings,
+        vec!["Deployment/my-app", "Service/my-app-svc", "Document 3"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn document_heading_decodes_escaped_kind() -> Result<()> {
+    let yaml = r#"
+kind: "Deploy\x6dent"
+metadata:
+  name: my-app
+"#;
+    let document = parse(yaml)?.unwrap();
+
+    assert_eq!(document_heading(&document, 1), "Deployment/my-app");
+    Ok(())
+}
+
+#[test]
+fn render_markdown_max_cell_lines() -> Result<()> {
+    let yaml = r#"
+script: |
+  one
+  two
+  three
+  four
+  five
+"#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        max_cell_lines: Some(2),
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+    assert!(output.contains("| script | one<br>two<br>… (3 more lines) |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_highlight_vars() -> Result<()> {
+    let document = parse("url: ${BASE_URL}/api")?.unwrap();
+
+    let options = RenderOptions {
+        highlight_vars: true,
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+    assert!(output.contains("| url | `${BASE_URL}`/api |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_show_required() -> Result<()> {
+    let yaml = r#"
+        # @required
+        # The name of the service
+        name: api
+        port: 80
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        show_required: true,
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+    assert!(output.contains("| Name | Value | Required | Description |"));
+    assert!(output.contains("| name | api | Yes | The name of the service |"));
+    assert!(output.contains("| port | 80 | No |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_deeply_indented_mapping() -> Result<()> {
+    let yaml = "key:\n    deeply: value\nother:\n        nested:\n              inner: 1\n";
+    let document = parse(yaml)?.unwrap();
+
+    let output = render_markdown(&document, &RenderOptions::default())?;
+    assert!(output.contains("| key.deeply | value |  |"));
+    assert!(output.contains("| other.nested.inner | 1 |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_strip_ansi() -> Result<()> {
+    let document = parse(r#"status: "\e[31mfailed\e[0m""#)?.unwrap();
+
+    let options = RenderOptions {
+        strip_ansi: true,
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+    assert!(output.contains("| status | failed |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_anchors_are_deterministic() -> Result<()> {
+    let yaml = r#"
+        a.b: 1
+        a-b: 2
+        a:
+          b: 3
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        anchors: true,
+        sort: SortOrder::Keys,
+        ..Default::default()
+    };
+    let first = render_markdown(&document, &options)?;
+    let second = render_markdown(&parse(yaml)?.unwrap(), &options)?;
+    assert_eq!(first, second);
+
+    assert!(first.contains(r#"| <a id="a-b"></a>a.b | 1 |  |"#));
+    assert!(first.contains(r#"| <a id="a-b-1"></a>a-b | 2 |  |"#));
+    assert!(first.contains(r#"| <a id="a-b-2"></a>a.b | 3 |  |"#));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_levels_summarizes_deeper_values() -> Result<()> {
+    let yaml = r#"
+        name: api
+        resources:
+          limits:
+            cpu: 100m
+          ports: [80, 443]
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        levels: Some(1),
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+
+    let rows: Vec<&str> = output.lines().filter(|l| l.starts_with("| ")).collect();
+    assert_eq!(
+        rows,
+        vec![
+            "| Name | Value | Description |",
+            "| name | api |  |",
+            "| resources | {limits: {cpu: 100m}, ports: [80, 443]} |  |",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_inline_single_maps() -> Result<()> {
+    let yaml = r#"
+        resources:
+          cpu: 100m
+        limits:
+          cpu: 200m
+          memory: 1Gi
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        inline_single_maps: true,
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+    assert!(output.contains("| resources | cpu: 100m |  |"));
+    assert!(output.contains("| limits.cpu | 200m |  |"));
+    assert!(output.contains("| limits.memory | 1Gi |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_inherit_list_descriptions() -> Result<()> {
+    let yaml = r#"
+        name: web
+        # ports exposed by the service
+        ports:
+          - 80
+          # only when tls is enabled
+          - 443
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let output = render_markdown(&document, &RenderOptions::default())?;
+    assert!(output.contains("| ports.0 | 80 |  |"));
+
+    let options = RenderOptions {
+        inherit_list_descriptions: true,
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+    assert!(output.contains("| ports.0 | 80 | ports exposed by the service |"));
+    assert!(output.contains("| ports.1 | 443 | only when tls is enabled |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_inherit_list_descriptions_within_list_of_maps() -> Result<()> {
+    let yaml = r#"
+        # containers run by the pod
+        containers:
+          - name: web
+            # port the container listens on
+            port: 8080
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        inherit_list_descriptions: true,
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+    assert!(output.contains("| containers.0.name | web | containers run by the pod |"));
+    assert!(output.contains("| containers.0.port | 8080 | port the container listens on |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_flow_and_block_sequences_match() -> Result<()> {
+    let flow = parse("ports: [80, 443]")?.unwrap();
+    let block = parse("ports:\n  - 80\n  - 443\n")?.unwrap();
+
+    let options = RenderOptions::default();
+    assert_eq!(
+        render_markdown(&flow, &options)?,
+        render_markdown(&block, &options)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_stream_matches_buffered() -> Result<()> {
+    let yaml = r#"
+        # The name of the service
+        name: truman
+        ports: [80, 443]
+        # @required
+        database:
+          host: localhost # Where the database lives
+          script: |
+            CREATE TABLE users;
+            CREATE INDEX users_id;
+        labels: {}
+        name_alias: truman
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    for options in [
+        RenderOptions::default(),
+        RenderOptions {
+            number_rows: true,
+            show_required: true,
+            anchors: true,
+            max_cell_lines: Some(1),
+            ..Default::default()
+        },
+    ] {
+        let mut streamed = Vec::new();
+        render_markdown_stream(&document, &options, &mut streamed)?;
+        assert_eq!(
+            String::from_utf8(streamed)?,
+            render_markdown(&document, &options)?
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_show_definitions() -> Result<()> {
+    let yaml = r#"
+        # the shared image
+        image: &image nginx:1.27
+        resources: &limits
+          cpu: 100m
+          memory: 1Gi
+        web:
+          image: *image
+          resources: *limits
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        show_definitions: true,
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+
+    let definitions = output.find("### Definitions").unwrap();
+    assert!(definitions < output.find("| Name | Value | Description |").unwrap());
+    assert!(output.contains(
+        "| Anchor | Value | Description |\n\
+         |--------|-------|-------------|\n\
+         | image | nginx:1.27 | the shared image |\n\
+         | limits | {cpu: 100m, memory: 1Gi} |  |\n"
+    ));
+
+    let output = render_markdown(&document, &RenderOptions::default())?;
+    assert!(!output.contains("### Definitions"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_links_aliases_to_definitions() -> Result<()> {
+    let yaml = r#"
+        image: &image nginx:1.27
+        resources: &limits
+          cpu: 100m
+        web:
+          image: *image
+          resources: *limits
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        show_definitions: true,
+        anchors: true,
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+
+    assert!(output.contains("| <a id=\"defs-image\"></a>image | nginx:1.27 |  |"));
+    assert!(
+        output.contains("| <a id=\"web-image\"></a>web.image | nginx:1.27 [↑](#defs-image) |  |")
+    );
+    assert!(output.contains(
+        "| <a id=\"web-resources-cpu\"></a>web.resources.cpu | 100m [↑](#defs-limits) |  |"
+    ));
+    assert!(output.contains("| <a id=\"image\"></a>image | nginx:1.27 |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_reindexes_aliased_lists() -> Result<()> {
+    let yaml = r#"
+        ports: &ports
+          - 80
+          - 443
+        web:
+          ports: *ports
+        jobs:
+          - *ports
+          - *ports
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let mut names = vec![];
+    render_markdown_with_hook(&document, &RenderOptions::default(), |rows| {
+        names = rows.iter().map(|row| row.name.clone()).collect();
+    })?;
+    assert_eq!(
+        names,
+        vec![
+            "ports.0",
+            "ports.1",
+            "web.ports.0",
+            "web.ports.1",
+            "jobs.0.0",
+            "jobs.0.1",
+            "jobs.1.0",
+            "jobs.1.1",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_list_tables() -> Result<()> {
+    let yaml = r#"
+        spec:
+          replicas: 2
+          containers:
+            - name: app
+              image: nginx
+            - name: sidecar
+              image: envoy
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        list_tables: true,
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+
+    assert!(output.contains("| spec.replicas | 2 |  |"));
+    assert!(!output.contains("spec.containers.0"));
+    assert!(output.ends_with(
+        "\n### spec.containers\n\n\
+         | name | image |\n\
+         |------|-------|\n\
+         | app | nginx |\n\
+         | sidecar | envoy |\n"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_code_keys() -> Result<()> {
+    let yaml = r#"
+        annotations:
+          nginx.ingress.kubernetes.io/rewrite-target: /
+        "a`b|c": 1
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        code_keys: true,
+        anchors: true,
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+    assert!(output.contains(
+        "| <a id=\"annotations-nginx-ingress-kubernetes-io-rewrite-target\"></a>\
+         `annotations.nginx.ingress.kubernetes.io/rewrite-target` | / |  |"
+    ));
+    assert!(output.contains("| <a id=\"a-b-c\"></a>``a`b\\|c`` | 1 |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_nested_empty_flow_collections() -> Result<()> {
+    let document = parse("config: {}\ntags: []\nnested:\n  labels: {}")?.unwrap();
+    let output = render_markdown(&document, &RenderOptions::default())?;
+
+    assert!(output.contains("| config | {} |  |"));
+    assert!(output.contains("| tags | [] |  |"));
+    assert!(output.contains("| nested.labels | {} |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_root_literal_block_scalar() -> Result<()> {
+    let document = parse("|\n  line1\n  line2")?.unwrap();
+    assert_eq!(document.root.value.type_name(), "string");
+
+    let output = render_markdown(&document, &RenderOptions::default())?;
+    assert!(output.contains("|  | line1<br>line2 |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_with_hook_appends_row() -> Result<()> {
+    let document = parse("name: truman\nage: 42")?.unwrap();
+    let options = RenderOptions {
+        number_rows: true,
+        ..Default::default()
+    };
+    let output = render_markdown_with_hook(&document, &options, |rows| {
+        rows.push(TableRow {
+            name: "generated".to_string(),
+            value: "yes".to_string(),
+            description: "added by a hook".to_string(),
+            ..Default::default()
+        });
+    })?;
+
+    assert!(output.contains("| 2 | age | 42 |  |"));
+    assert!(output.contains("| 3 | generated | yes | added by a hook |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_with_hook_anchors_added_rows() -> Result<()> {
+    let document = parse("name: truman")?.unwrap();
+    let options = RenderOptions {
+        anchors: true,
+        ..Default::default()
+    };
+    let output = render_markdown_with_hook(&document, &options, |rows| {
+        for name in ["generated", "name"] {
+            rows.push(TableRow {
+                name: name.to_string(),
+                value: "yes".to_string(),
+                ..Default::default()
+            });
+        }
+    })?;
+
+    assert!(output.contains("| <a id=\"name\"></a>name | truman |  |"));
+    assert!(output.contains("| <a id=\"generated\"></a>generated | yes |  |"));
+    assert!(output.contains("| <a id=\"name-1\"></a>name | yes |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_with_hook_rejects_list_layout() -> Result<()> {
+    let document = parse("name: truman")?.unwrap();
+    let options = RenderOptions {
+        layout: Layout::List,
+        ..Default::default()
+    };
+
+    let result = render_markdown_with_hook(&document, &options, |_| {});
+    assert!(matches!(result, Err(YamError::ListLayoutHook)));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_wraps_descriptions() -> Result<()> {
+    let yaml = "# the address the server listens on for incoming requests\nhost: 0.0.0.0\n";
+    let document = parse(yaml)?.unwrap();
+    let options = RenderOptions {
+        wrap_width: Some(20),
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+
+    assert!(output.contains(
+        "| host | 0.0.0.0 | the address the<br>server listens on<br>for incoming<br>requests |"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_hides_internal_values() -> Result<()> {
+    let yaml = r#"
+        # the public name
+        name: api
+        # @internal tuning for the platform team
+        debug:
+          level: trace
+        port: 80
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let options = RenderOptions {
+        hide_marker: Some("@internal".to_string()),
+        ..Default::default()
+    };
+    let output = render_markdown(&document, &options)?;
+    assert!(output.contains("| name | api | the public name |"));
+    assert!(output.contains("| port | 80 |  |"));
+    assert!(!output.contains("debug"));
+
+    let output = render_markdown(&document, &RenderOptions::default())?;
+    assert!(output.contains("| debug.level | trace |  |"));
+
+    // The root shares the comment above its first key, but only that key is hidden
+    let document = parse("# @internal\ntoken: 1\nname: api\n")?.unwrap();
+    let output = render_markdown(&document, &options)?;
+    assert!(output.contains("| name | api |  |"));
+    assert!(!output.contains("token"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_unescapes_double_quoted_strings() -> Result<()> {
+    let document = parse(r#"motd: "hello\nworld""#)?.unwrap();
+    let output = render_markdown(&document, &RenderOptions::default())?;
+    assert!(output.contains("| motd | hello<br>world |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_keeps_indentation_of_literal_block() -> Result<()> {
+    let yaml = "script: |\n  if x:\n    run()\n  done\n";
+    let document = parse(yaml)?.unwrap();
+
+    let output = render_markdown(&document, &RenderOptions::default())?;
+    assert!(output.contains("| script | if x:<br>&nbsp;&nbsp;run()<br>done |  |"));
+
+    Ok(())
+}
+
+#[test]
+fn render_markdown_describes_document_with_root_comment() -> Result<()> {
+    let document = parse("{x: 1, y: 2} # note")?.unwrap();
+    let output = render_markdown(&document, &RenderOptions::default())?;
+    assert!(output.starts_with("\nnote\n\n| Name | Value | Description |"));
+    assert!(output.contains("| x | 1 |  |"));
+
+    // A comment above a block document describes its first key instead
+    let document = parse("# the name\nname: api\n")?.unwrap();
+    let output = render_markdown(&document, &RenderOptions::default())?;
+    assert!(output.starts_with("\n| Name | Value | Description |"));
+    assert!(output.contains("| name | api | the name |"));
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use anyhow::{Ok, Result};
-use yam::parser::*;
+use yam::{error::YamError, parser::*};
 
 #[test]
 fn parse_scalar_integer() -> Result<()> {
@@ -18,6 +18,30 @@ fn parse_scalar_integer_with_comment() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn parse_scalar_with_trailing_whitespace_lines() -> Result<()> {
+    let document = parse("42   \n\n   \n")?.unwrap();
+    assert_eq!(document.root.value, ScalarType::Integer(42));
+    assert_eq!(document.root.comment, None);
+
+    Ok(())
+}
+
+// A comment below a value describes nothing that follows it, so it is ignored
+// rather than attached to the root
+#[test]
+fn parse_scalar_with_trailing_comment() -> Result<()> {
+    let document = parse("42   \n# trailing")?.unwrap();
+    assert_eq!(document.root.value, ScalarType::Integer(42));
+    assert_eq!(document.root.comment, None);
+
+    let document = parse("42 # inline\n\n# trailing\n")?.unwrap();
+    assert_eq!(document.root.value, ScalarType::Integer(42));
+    assert_eq!(document.root.comment, Some("inline".to_string()));
+
+    Ok(())
+}
+
 #[test]
 fn parse_scalar_integer_negative() -> Result<()> {
     let document = parse("-42")?.unwrap();
@@ -87,6 +111,22 @@ fn parse_scalar_float_scientific_notation_negative() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn parse_scalar_float_scientific_notation_without_fraction() -> Result<()> {
+    let document = parse("1e3")?.unwrap();
+    assert_eq!(document.root.value, ScalarType::Float(1000.0));
+
+    Ok(())
+}
+
+#[test]
+fn parse_scalar_float_scientific_notation_negative_exponent() -> Result<()> {
+    let document = parse("1E-2")?.unwrap();
+    assert_eq!(document.root.value, ScalarType::Float(0.01));
+
+    Ok(())
+}
+
 #[test]
 fn parse_scalar_float_positive_infinity() -> Result<()> {
     let document = parse(".inf")?.unwrap();
@@ -194,10 +234,44 @@ fn parse_scalar_empty_string_single_quoted() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn parse_scalar_empty_strings_differ_by_style() -> Result<()> {
+    let double = parse(r#""""#)?.unwrap().root;
+    let single = parse("''")?.unwrap().root;
+    assert_eq!(double.value, single.value);
+    assert_eq!(double.style, ScalarStyle::DoubleQuoted);
+    assert_eq!(single.style, ScalarStyle::SingleQuoted);
+
+    Ok(())
+}
+
+#[test]
+fn parse_scalar_quoted_number_keeps_style() -> Result<()> {
+    let document = parse(
+        r#"
+        port: "8080"
+        replicas: 3
+        "#,
+    )?
+    .unwrap();
+    let port = document.root.get("port").unwrap();
+    assert_eq!(port.value, ScalarType::String("8080"));
+    assert_eq!(port.style, ScalarStyle::DoubleQuoted);
+    assert_eq!(
+        document.root.get("replicas").unwrap().style,
+        ScalarStyle::Plain
+    );
+
+    Ok(())
+}
+
 #[test]
 fn parse_scalar_string_with_escape_newline() -> Result<()> {
     let document = parse(r#""hello\nworld""#)?.unwrap();
-    assert_eq!(document.root.value, ScalarType::String("hello\\nworld"));
+    assert_eq!(
+        document.root.value,
+        ScalarType::Text("hello\nworld".to_string())
+    );
 
     Ok(())
 }
@@ -205,7 +279,10 @@ fn parse_scalar_string_with_escape_newline() -> Result<()> {
 #[test]
 fn parse_scalar_string_with_escape_tab() -> Result<()> {
     let document = parse(r#""hello\tworld""#)?.unwrap();
-    assert_eq!(document.root.value, ScalarType::String("hello\\tworld"));
+    assert_eq!(
+        document.root.value,
+        ScalarType::Text("hello\tworld".to_string())
+    );
 
     Ok(())
 }
@@ -213,7 +290,10 @@ fn parse_scalar_string_with_escape_tab() -> Result<()> {
 #[test]
 fn parse_scalar_string_with_escape_backslash() -> Result<()> {
     let document = parse(r#""hello\\world""#)?.unwrap();
-    assert_eq!(document.root.value, ScalarType::String("hello\\\\world"));
+    assert_eq!(
+        document.root.value,
+        ScalarType::Text("hello\\world".to_string())
+    );
 
     Ok(())
 }
@@ -221,7 +301,10 @@ fn parse_scalar_string_with_escape_backslash() -> Result<()> {
 #[test]
 fn parse_scalar_string_with_escape_quote() -> Result<()> {
     let document = parse(r#""hello\"world""#)?.unwrap();
-    assert_eq!(document.root.value, ScalarType::String("hello\\\"world"));
+    assert_eq!(
+        document.root.value,
+        ScalarType::Text("hello\"world".to_string())
+    );
 
     Ok(())
 }
@@ -229,7 +312,10 @@ fn parse_scalar_string_with_escape_quote() -> Result<()> {
 #[test]
 fn parse_scalar_string_with_escape_carriage_return() -> Result<()> {
     let document = parse(r#""hello\rworld""#)?.unwrap();
-    assert_eq!(document.root.value, ScalarType::String("hello\\rworld"));
+    assert_eq!(
+        document.root.value,
+        ScalarType::Text("hello\rworld".to_string())
+    );
 
     Ok(())
 }
@@ -237,7 +323,10 @@ fn parse_scalar_string_with_escape_carriage_return() -> Result<()> {
 #[test]
 fn parse_scalar_string_with_escape_null() -> Result<()> {
     let document = parse(r#""hello\0world""#)?.unwrap();
-    assert_eq!(document.root.value, ScalarType::String("hello\\0world"));
+    assert_eq!(
+        document.root.value,
+        ScalarType::Text("hello\0world".to_string())
+    );
 
     Ok(())
 }
@@ -245,7 +334,7 @@ fn parse_scalar_string_with_escape_null() -> Result<()> {
 #[test]
 fn parse_scalar_string_with_unicode_escape_short() -> Result<()> {
     let document = parse(r#""\u0041""#)?.unwrap();
-    assert_eq!(document.root.value, ScalarType::String("\\u0041"));
+    assert_eq!(document.root.value, ScalarType::Text("A".to_string()));
 
     Ok(())
 }
@@ -253,7 +342,7 @@ fn parse_scalar_string_with_unicode_escape_short() -> Result<()> {
 #[test]
 fn parse_scalar_string_with_unicode_escape_long() -> Result<()> {
     let document = parse(r#""\U00000041""#)?.unwrap();
-    assert_eq!(document.root.value, ScalarType::String("\\U00000041"));
+    assert_eq!(document.root.value, ScalarType::Text("A".to_string()));
 
     Ok(())
 }
@@ -261,7 +350,7 @@ fn parse_scalar_string_with_unicode_escape_long() -> Result<()> {
 #[test]
 fn parse_scalar_string_with_unicode_emoji() -> Result<()> {
     let document = parse(r#""\U0001F600""#)?.unwrap();
-    assert_eq!(document.root.value, ScalarType::String("\\U0001F600"));
+    assert_eq!(document.root.value, ScalarType::Text("😀".to_string()));
 
     Ok(())
 }
@@ -269,7 +358,26 @@ fn parse_scalar_string_with_unicode_emoji() -> Result<()> {
 #[test]
 fn parse_scalar_string_single_quote_escape() -> Result<()> {
     let document = parse("'it''s'")?.unwrap();
-    assert_eq!(document.root.value, ScalarType::String("it''s"));
+    assert_eq!(document.root.value, ScalarType::Text("it's".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn parse_scalar_string_with_escaped_line_break() -> Result<()> {
+    let document = parse("\"hello \\\n    world\\x21\"")?.unwrap();
+    assert_eq!(
+        document.root.value,
+        ScalarType::Text("hello world!".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_scalar_string_without_escapes_is_borrowed() -> Result<()> {
+    let document = parse(r#""hello world""#)?.unwrap();
+    assert_eq!(document.root.value, ScalarType::String("hello world"));
 
     Ok(())
 }
@@ -352,35 +460,42 @@ fn parse_scalar_list() -> Result<()> {
         items[0],
         Scalar {
             value: ScalarType::Integer(42),
-            comment: None
+            comment: None,
+            ..Default::default()
         }
     );
     assert_eq!(
         items[1],
         Scalar {
             value: ScalarType::Float(42.56),
-            comment: None
+            comment: None,
+            ..Default::default()
         }
     );
     assert_eq!(
         items[2],
         Scalar {
             value: ScalarType::Boolean(true),
-            comment: None
+            comment: None,
+            ..Default::default()
         }
     );
     assert_eq!(
         items[3],
         Scalar {
             value: ScalarType::String("hello, world!"),
-            comment: None
+            comment: None,
+            style: ScalarStyle::DoubleQuoted,
+            ..Default::default()
         }
     );
     assert_eq!(
         items[4],
         Scalar {
             value: ScalarType::String("good afternoon, good evening, and good night"),
-            comment: None
+            comment: None,
+            style: ScalarStyle::SingleQuoted,
+            ..Default::default()
         }
     );
 
@@ -406,14 +521,16 @@ fn parse_scalar_list_with_comments() -> Result<()> {
         items[0],
         Scalar {
             value: ScalarType::Integer(42),
-            comment: Some("comment for item 1".to_string())
+            comment: Some("comment for item 1".to_string()),
+            ..Default::default()
         }
     );
     assert_eq!(
         items[1],
         Scalar {
             value: ScalarType::Float(42.56),
-            comment: Some("comment for item 2".to_string())
+            comment: Some("comment for item 2".to_string()),
+            ..Default::default()
         }
     );
 
@@ -434,21 +551,24 @@ fn parse_scalar_list_with_flow_sequence() -> Result<()> {
         items[0],
         Scalar {
             value: ScalarType::Integer(1),
-            comment: None
+            comment: None,
+            ..Default::default()
         }
     );
     assert_eq!(
         items[1],
         Scalar {
             value: ScalarType::Integer(2),
-            comment: None
+            comment: None,
+            ..Default::default()
         }
     );
     assert_eq!(
         items[2],
         Scalar {
             value: ScalarType::Integer(3),
-            comment: None
+            comment: None,
+            ..Default::default()
         }
     );
 
@@ -481,6 +601,7 @@ fn parse_scalar_map() -> Result<()> {
                 Scalar {
                     value: ScalarType::String("truman"),
                     comment: None,
+                    ..Default::default()
                 }
             );
         }
@@ -508,6 +629,7 @@ fn parse_scalar_map_with_comments() -> Result<()> {
                 Scalar {
                     value: ScalarType::Integer(1),
                     comment: Some("comment for x".to_string()),
+                    ..Default::default()
                 }
             );
             assert_eq!(map[1].key, "y");
@@ -516,6 +638,7 @@ fn parse_scalar_map_with_comments() -> Result<()> {
                 Scalar {
                     value: ScalarType::Integer(2),
                     comment: Some("comment for y".to_string()),
+                    ..Default::default()
                 }
             );
         }
@@ -538,6 +661,7 @@ fn parse_scalar_map_with_empty_value() -> Result<()> {
                 Scalar {
                     value: ScalarType::Null,
                     comment: None,
+                    ..Default::default()
                 }
             );
         }
@@ -559,6 +683,7 @@ fn parse_scalar_map_with_flow_sequence() -> Result<()> {
                 Scalar {
                     value: ScalarType::Integer(1),
                     comment: None,
+                    ..Default::default()
                 }
             );
             assert_eq!(map[1].key, "y");
@@ -567,6 +692,7 @@ fn parse_scalar_map_with_flow_sequence() -> Result<()> {
                 Scalar {
                     value: ScalarType::Integer(2),
                     comment: None,
+                    ..Default::default()
                 }
             );
         }
@@ -599,6 +725,7 @@ fn parse_scalar_map_with_flow_sequence_only_keys() -> Result<()> {
                 Scalar {
                     value: ScalarType::Null,
                     comment: None,
+                    ..Default::default()
                 }
             );
             assert_eq!(map[1].key, "y");
@@ -607,6 +734,7 @@ fn parse_scalar_map_with_flow_sequence_only_keys() -> Result<()> {
                 Scalar {
                     value: ScalarType::Null,
                     comment: None,
+                    ..Default::default()
                 }
             );
         }
@@ -687,13 +815,12 @@ fn parse_block_scalar_literal() -> Result<()> {
         ScalarType::Map(ref map) => {
             assert_eq!(map.len(), 1);
             assert_eq!(map[0].key, "key");
-            match &map[0].value.value {
-                ScalarType::String(s) => {
-                    assert!(s.contains("this is a multiline"));
-                    assert!(s.contains("string spread over multiple lines"));
-                }
-                _ => panic!("value should be a string"),
-            }
+            assert_eq!(
+                map[0].value.value,
+                ScalarType::Text(
+                    "this is a multiline\nstring spread over multiple lines".to_string()
+                )
+            );
         }
         _ => panic!("root node should contain a map scalar"),
     }
@@ -708,17 +835,198 @@ fn parse_block_scalar_with_chomping() -> Result<()> {
   string spread over multiple lines
 "#;
 
+    let document = parse(yaml)?.unwrap();
+    assert_eq!(
+        document.root.get("key").unwrap().value,
+        ScalarType::Text("this is a multiline\nstring spread over multiple lines\n".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_block_scalar_with_indentation_indicator() -> Result<()> {
+    let yaml =
+        "key: |2\n    indented\n  text\n\n  more\nnested:\n  list:\n    - |1\n      a\n       b\n";
+
+    let document = parse(yaml)?.unwrap();
+    assert_eq!(
+        document.root.get("key").unwrap().value,
+        ScalarType::Text("  indented\ntext\n\nmore\n".to_string())
+    );
+    assert_eq!(
+        document.root.get_path("nested.list.0").unwrap().value,
+        ScalarType::Text(" a\n  b\n".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_block_scalar_folded_with_blank_line() -> Result<()> {
+    let yaml = "key: >\n  first\n  paragraph\n\n  second\n    indented\n  paragraph\nnext: 1\n";
+
+    let document = parse(yaml)?.unwrap();
+    assert_eq!(
+        document.root.get("key").unwrap().value,
+        ScalarType::Text("first paragraph\nsecond\n  indented\nparagraph\n".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_block_scalar_chomping_indicators() -> Result<()> {
+    let yaml =
+        "strip: |-\n  text\n\nclip: |\n  text\n\nkeep: |+\n  text\n\nindented: |2-\n   text\n";
+
+    let document = parse(yaml)?.unwrap();
+    let value = |key| document.root.get(key).unwrap().value.clone();
+    assert_eq!(value("strip"), ScalarType::Text("text".to_string()));
+    assert_eq!(value("clip"), ScalarType::Text("text\n".to_string()));
+    assert_eq!(value("keep"), ScalarType::Text("text\n\n".to_string()));
+    assert_eq!(value("indented"), ScalarType::Text(" text".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn parse_block_scalar_styles() -> Result<()> {
+    let yaml = r#"literal: |
+  kept as written
+folded: >
+  joined into one line
+"#;
+    let document = parse(yaml)?.unwrap();
+    assert_eq!(
+        document.root.get("literal").unwrap().style,
+        ScalarStyle::Literal
+    );
+    assert_eq!(
+        document.root.get("folded").unwrap().style,
+        ScalarStyle::Folded
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_block_scalar_folded() -> Result<()> {
+    let yaml = r#"key: >
+  this is a multiline
+  string that will be folded"#;
+
     let document = parse(yaml)?.unwrap();
     match document.root.value {
         ScalarType::Map(ref map) => {
             assert_eq!(map.len(), 1);
             assert_eq!(map[0].key, "key");
-            match &map[0].value.value {
-                ScalarType::String(s) => {
-                    assert!(s.contains("this is a multiline"));
-                    assert!(s.contains("string spread over multiple lines"));
+            assert_eq!(
+                map[0].value.value,
+                ScalarType::Text("this is a multiline string that will be folded".to_string())
+            );
+        }
+        _ => panic!("root node should contain a map scalar"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse_scalar_map_with_flow_sequence_spaced_key() -> Result<()> {
+    let document = parse("{ name : value }")?.unwrap();
+    match document.root.value {
+        ScalarType::Map(ref map) => {
+            assert_eq!(map.len(), 1);
+            assert_eq!(map[0].key, "name");
+            assert_eq!(map[0].value.value, ScalarType::String("value"));
+        }
+        _ => panic!("root node should contain a map scalar"),
+    }
+    Ok(())
+}
+
+fn root_keys<'a>(documents: &'a [Document<'_>]) -> Vec<Vec<&'a str>> {
+    documents
+        .iter()
+        .map(|document| keys(&document.root))
+        .collect()
+}
+
+#[test]
+fn parse_all_documents_in_stream() -> Result<()> {
+    let documents = parse_all("a: 1\n---\nb: 2\n---\nc: 3\n")?;
+    assert_eq!(root_keys(&documents), vec![vec!["a"], vec!["b"], vec!["c"]]);
+
+    Ok(())
+}
+
+#[test]
+fn parse_all_with_leading_separator() -> Result<()> {
+    let documents = parse_all("---\na: 1\n---\nb: 2\n")?;
+    assert_eq!(root_keys(&documents), vec![vec!["a"], vec!["b"]]);
+
+    Ok(())
+}
+
+#[test]
+fn parse_all_skips_trailing_empty_document() -> Result<()> {
+    let documents = parse_all("a: 1\n---\n---\nb: 2\n---\n")?;
+    assert_eq!(root_keys(&documents), vec![vec!["a"], vec!["b"]]);
+
+    Ok(())
+}
+
+#[test]
+fn parse_all_with_document_end_markers() -> Result<()> {
+    let documents = parse_all("a: 1\n...\n---\nb: 2\n...\n")?;
+    assert_eq!(root_keys(&documents), vec![vec!["a"], vec!["b"]]);
+
+    Ok(())
+}
+
+#[test]
+fn parse_document_try_from() -> Result<()> {
+    let document = Document::try_from("name: x")?;
+    match document.root.value {
+        ScalarType::Map(ref map) => {
+            assert_eq!(map.len(), 1);
+            assert_eq!(map[0].key, "name");
+            assert_eq!(map[0].value.value, ScalarType::String("x"));
+        }
+        _ => panic!("root node should contain a map scalar"),
+    }
+    Ok(())
+}
+
+#[test]
+fn parse_document_try_from_empty() {
+    let result = Document::try_from("");
+    assert!(matches!(result, Err(ParseError::EmptyDocument)));
+}
+
+#[test]
+fn parse_scalar_map_nested_does_not_inherit_child_comment() -> Result<()> {
+    let yaml = r#"
+        # comment for image
+        image:
+          # comment for registry
+          registry: docker.io
+        tags:
+          # comment for first tag
+          - latest
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    match document.root.value {
+        ScalarType::Map(ref map) => {
+            assert_eq!(map[0].value.comment, Some("comment for image".to_string()));
+            assert_eq!(map[1].value.comment, None);
+            match map[1].value.value {
+                ScalarType::List(ref items) => {
+                    assert_eq!(items[0].comment, Some("comment for first tag".to_string()));
                 }
-                _ => panic!("value should be a string"),
+                _ => panic!("child node should contain a list scalar"),
             }
         }
         _ => panic!("root node should contain a map scalar"),
@@ -728,26 +1036,884 @@ fn parse_block_scalar_with_chomping() -> Result<()> {
 }
 
 #[test]
-fn parse_block_scalar_folded() -> Result<()> {
-    let yaml = r#"key: >
-  this is a multiline
-  string that will be folded"#;
+fn parse_scalar_map_with_helm_comment_style() -> Result<()> {
+    let yaml = r#"
+        # an ordinary comment
+        x: 1
+        # -- the description for y
+        # spread over two lines
+        y: 2
+        "#;
+
+    let options = ParseOptions {
+        comment_style: CommentStyle::Helm,
+        ..Default::default()
+    };
+    let document = parse_with_options(yaml, &options)?.unwrap();
+    match document.root.value {
+        ScalarType::Map(ref map) => {
+            assert_eq!(map[0].value.comment, None);
+            assert_eq!(
+                map[1].value.comment,
+                Some("the description for y spread over two lines".to_string())
+            );
+        }
+        _ => panic!("root node should contain a map scalar"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse_scalar_map_with_default_and_type_annotations() -> Result<()> {
+    let yaml = r#"
+        # the port the service listens on
+        # @default -- 8080
+        # @type -- integer
+        port:
+        "#;
 
     let document = parse(yaml)?.unwrap();
     match document.root.value {
         ScalarType::Map(ref map) => {
-            assert_eq!(map.len(), 1);
-            assert_eq!(map[0].key, "key");
-            match &map[0].value.value {
-                ScalarType::String(s) => {
-                    assert!(s.contains("this is a multiline"));
-                    assert!(s.contains("string that will be folded"));
+            assert_eq!(
+                map[0].value,
+                Scalar {
+                    value: ScalarType::Null,
+                    comment: Some("the port the service listens on".to_string()),
+                    explicit_default: Some("8080".to_string()),
+                    explicit_type: Some("integer".to_string()),
+                    required: false,
+                    alias: None,
+                    style: ScalarStyle::Plain,
                 }
-                _ => panic!("value should be a string"),
-            }
+            );
         }
         _ => panic!("root node should contain a map scalar"),
     }
 
     Ok(())
 }
+
+#[test]
+fn parse_comment_only_document() -> Result<()> {
+    let yaml = r#"
+        # this file is intentionally empty
+        # and only contains comments
+        "#;
+
+    assert!(parse(yaml)?.is_none());
+
+    let options = ParseOptions {
+        keep_comment_only: true,
+        ..Default::default()
+    };
+    let document = parse_with_options(yaml, &options)?.unwrap();
+    assert_eq!(document.root.value, ScalarType::Null);
+    assert_eq!(
+        document.root.comment,
+        Some("this file is intentionally empty and only contains comments".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_scalar_list_with_unquoted_urls() -> Result<()> {
+    let yaml = r#"
+        - http://example.com
+        - https://example.com:8443/path
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let items = match &document.root.value {
+        ScalarType::List(items) => items,
+        _ => panic!("root node should contain a list scalar"),
+    };
+
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].value, ScalarType::String("http://example.com"));
+    assert_eq!(
+        items[1].value,
+        ScalarType::String("https://example.com:8443/path")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn scalar_map_values_redacts_strings() -> Result<()> {
+    let yaml = r#"
+        database:
+          # the database user
+          user: admin
+          password: hunter2
+          port: 5432
+        hosts:
+          - db.example.com
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let redacted = document.root.map_values(|value| match value {
+        ScalarType::String(_) => ScalarType::String("redacted"),
+        other => other.clone(),
+    });
+
+    let expected = parse(
+        r#"
+        database:
+          # the database user
+          user: redacted
+          password: redacted
+          port: 5432
+        hosts:
+          - redacted
+        "#,
+    )?
+    .unwrap();
+    assert_eq!(redacted, expected.root);
+
+    Ok(())
+}
+
+#[test]
+fn parse_bytes_valid_utf8() -> Result<()> {
+    let document = parse_bytes("name: truman".as_bytes())?.unwrap();
+    match document.root.value {
+        ScalarType::Map(ref map) => {
+            assert_eq!(map[0].key, "name");
+            assert_eq!(map[0].value.value, ScalarType::String("truman"));
+        }
+        _ => panic!("root node should contain a map scalar"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse_bytes_invalid_utf8_within_scalar() {
+    let result = parse_bytes(b"name: tru\xffman");
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "invalid UTF-8 at line 1, column 10"
+    );
+}
+
+#[test]
+fn parse_bytes_invalid_utf8_error_variant() {
+    let result = parse_bytes(b"name: tru\xffman");
+    assert!(matches!(
+        result,
+        Err(YamError::Parse(ParseError::InvalidUtf8 {
+            line: 1,
+            column: 10
+        }))
+    ));
+}
+
+#[test]
+fn parse_bytes_invalid_utf8_within_comment() {
+    let result = parse_bytes(b"name: truman\n# a bad \xff comment\nage: 42");
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "invalid UTF-8 at line 2, column 9"
+    );
+}
+
+#[test]
+fn parse_scalar_list_with_flow_sequence_comments() -> Result<()> {
+    let yaml = r#"
+        [
+          1, # one
+          2,
+          # three
+          3
+        ]
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let items = match &document.root.value {
+        ScalarType::List(items) => items,
+        _ => panic!("root node should contain a list scalar"),
+    };
+
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].comment, Some("one".to_string()));
+    assert_eq!(items[1].comment, None);
+    assert_eq!(items[2].comment, Some("three".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn document_depth_flat_map() -> Result<()> {
+    let document = parse("a: 1\nb: two")?.unwrap();
+    assert_eq!(document.depth(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn document_depth_nested_map() -> Result<()> {
+    let yaml = r#"
+        a: 1
+        b:
+          c: 2
+        "#;
+    let document = parse(yaml)?.unwrap();
+    assert_eq!(document.depth(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn parse_root_flow_mapping_with_trailing_comment() -> Result<()> {
+    let document = parse("{x: 1, y: 2} # note")?.unwrap();
+    assert_eq!(document.root.comment, Some("note".to_string()));
+
+    let map = match &document.root.value {
+        ScalarType::Map(map) => map,
+        _ => panic!("root node should contain a map scalar"),
+    };
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[0].value.comment, None);
+    assert_eq!(map[1].value.comment, None);
+
+    Ok(())
+}
+
+#[test]
+fn parse_whitespace_only_input() -> Result<()> {
+    assert!(parse("")?.is_none());
+    assert!(parse("  \n\t\n  ")?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn parse_scalar_list_wide_flow_sequence() -> Result<()> {
+    let items: Vec<String> = (0..5000).map(|n| n.to_string()).collect();
+    let yaml = format!("[{}]", items.join(", "));
+    let document = parse(&yaml)?.unwrap();
+
+    match document.root.value {
+        ScalarType::List(ref list) => {
+            assert_eq!(list.len(), 5000);
+            assert_eq!(list[4999].value, ScalarType::Integer(4999));
+        }
+        _ => panic!("root node should contain a list scalar"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse_alias_does_not_repeat_anchor_comment() -> Result<()> {
+    let yaml = r#"
+        # defaults shared by each service
+        defaults: &defaults
+          # the port to listen on
+          port: 80
+        api: *defaults
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let map = match &document.root.value {
+        ScalarType::Map(map) => map,
+        _ => panic!("root node should contain a map scalar"),
+    };
+    assert_eq!(map[0].key, "defaults");
+    assert_eq!(map[1].key, "api");
+    assert_eq!(
+        map[0].value.comment,
+        Some("defaults shared by each service".to_string())
+    );
+    match &map[0].value.value {
+        ScalarType::Map(anchored) => {
+            assert_eq!(
+                anchored[0].value.comment,
+                Some("the port to listen on".to_string())
+            );
+        }
+        _ => panic!("anchor should define a map scalar"),
+    }
+
+    match &map[1].value.value {
+        ScalarType::Map(aliased) => {
+            assert_eq!(aliased[0].key, "port");
+            assert_eq!(aliased[0].value.value, ScalarType::Integer(80));
+            assert_eq!(aliased[0].value.comment, None);
+        }
+        _ => panic!("alias should resolve to a map scalar"),
+    }
+    assert_eq!(map[1].value.comment, None);
+
+    Ok(())
+}
+
+#[test]
+fn parse_nested_container_takes_comment_of_its_key() -> Result<()> {
+    let yaml = r#"
+        # the database
+        database:
+          # the host
+          host: db
+        ports:
+          # the first port
+          - 80
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let root = &document.root;
+    assert_eq!(
+        root.get("database").unwrap().comment,
+        Some("the database".to_string())
+    );
+    assert_eq!(
+        root.get_path("database.host").unwrap().comment,
+        Some("the host".to_string())
+    );
+    assert_eq!(root.get("ports").unwrap().comment, None);
+    assert_eq!(
+        root.get_path("ports.0").unwrap().comment,
+        Some("the first port".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_undefined_alias() {
+    let result = parse("port: *missing");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("undefined alias *missing at line 1, column 7")
+    );
+}
+
+#[test]
+fn parse_core_schema_tags() -> Result<()> {
+    assert_eq!(
+        parse("!!str 42")?.unwrap().root.value,
+        ScalarType::String("42")
+    );
+    assert_eq!(
+        parse(r#"!!int "10""#)?.unwrap().root.value,
+        ScalarType::Integer(10)
+    );
+
+    let yaml = r#"
+        version: !!str 1.0
+        flags: !!int 0x10
+        ratio: !!float 3
+        enabled: !!bool 'true'
+        unset: !!null ''
+        custom: !secret 3
+        "#;
+    let document = parse(yaml)?.unwrap();
+    let value = |key| document.root.get(key).unwrap().value.clone();
+    assert_eq!(value("version"), ScalarType::String("1.0"));
+    assert_eq!(value("flags"), ScalarType::Integer(16));
+    assert_eq!(value("ratio"), ScalarType::Float(3.0));
+    assert_eq!(value("enabled"), ScalarType::Boolean(true));
+    assert_eq!(value("unset"), ScalarType::Null);
+    assert_eq!(value("custom"), ScalarType::Integer(3));
+
+    Ok(())
+}
+
+#[test]
+fn parse_tagged_value_that_cannot_be_coerced() {
+    let result = parse("port: !!int foo");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("cannot read value as !!int at line 1, column 7")
+    );
+}
+
+#[test]
+fn parse_timestamps_strictly() -> Result<()> {
+    let yaml = r#"
+        date: 2021-04-01
+        datetime: 2021-04-01T10:00:00.5+01:00
+        dashes: 1-2-3
+        sexagesimal: 12:34
+        month: 2021-13-01
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    match document.root.value {
+        ScalarType::Map(ref map) => {
+            assert_eq!(map[0].value.value, ScalarType::Timestamp("2021-04-01"));
+            assert_eq!(
+                map[1].value.value,
+                ScalarType::Timestamp("2021-04-01T10:00:00.5+01:00")
+            );
+            assert_eq!(map[2].value.value, ScalarType::String("1-2-3"));
+            assert_eq!(map[3].value.value, ScalarType::String("12:34"));
+            assert_eq!(map[4].value.value, ScalarType::String("2021-13-01"));
+        }
+        _ => panic!("root node should contain a map scalar"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse_timestamps_disabled() -> Result<()> {
+    let options = ParseOptions {
+        timestamps: false,
+        ..Default::default()
+    };
+    let document = parse_with_options("date: 2021-04-01", &options)?.unwrap();
+    match document.root.value {
+        ScalarType::Map(ref map) => {
+            assert_eq!(map[0].value.value, ScalarType::String("2021-04-01"));
+        }
+        _ => panic!("root node should contain a map scalar"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse_get_path() -> Result<()> {
+    let yaml = r#"
+        ingress:
+          tls:
+            - secret: tls-cert
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let secret = document.root.get_path("ingress.tls.0.secret").unwrap();
+    assert_eq!(secret.value, ScalarType::String("tls-cert"));
+    assert!(document.root.get_path("ingress.tls.1").is_none());
+    assert!(document.root.get_path("ingress.missing").is_none());
+    assert_eq!(document.root.get_path(""), Some(&document.root));
+
+    Ok(())
+}
+
+#[test]
+fn parse_nested_map_after_comment_and_tag() -> Result<()> {
+    let yaml = r#"
+        key: # a comment before the nested map
+          nested: 1
+        tagged: !!map # a tag and comment before the nested map
+          nested: 2
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let nested = |key: &str| document.root.get_path(key).map(|s| s.value.clone());
+    assert_eq!(nested("key.nested"), Some(ScalarType::Integer(1)));
+    assert_eq!(nested("tagged.nested"), Some(ScalarType::Integer(2)));
+
+    let comment = |key: &str| document.root.get_path(key).unwrap().comment.as_deref();
+    assert_eq!(comment("key"), Some("a comment before the nested map"));
+    assert_eq!(
+        comment("tagged"),
+        Some("a tag and comment before the nested map")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_comment_after_key_describes_value_on_next_line() -> Result<()> {
+    let yaml = r#"
+        key: # describes the value below
+          value
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let key = document.root.get("key").unwrap();
+    assert_eq!(key.value, ScalarType::String("value"));
+    assert_eq!(key.comment.as_deref(), Some("describes the value below"));
+
+    Ok(())
+}
+
+#[test]
+fn parse_hash_within_scalars_is_not_a_comment() -> Result<()> {
+    let yaml = r##"
+        double: "# literal"
+        single: '# literal'
+        plain: a#b
+        quoted: "a # b" # trailing
+        "##;
+
+    let document = parse(yaml)?.unwrap();
+    let value = |key: &str| document.root.get(key).unwrap();
+
+    assert_eq!(value("double").value, ScalarType::String("# literal"));
+    assert_eq!(value("double").comment, None);
+    assert_eq!(value("single").value, ScalarType::String("# literal"));
+    assert_eq!(value("single").comment, None);
+    assert_eq!(value("plain").value, ScalarType::String("a#b"));
+    assert_eq!(value("plain").comment, None);
+    assert_eq!(value("quoted").value, ScalarType::String("a # b"));
+    assert_eq!(value("quoted").comment, Some("trailing".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn parse_merge_comment_groups() -> Result<()> {
+    let yaml = r#"
+        # the first group
+        # spread over two lines
+
+        # the second group
+        key: 1
+        "#;
+
+    let options = ParseOptions {
+        merge_comment_groups: true,
+        ..Default::default()
+    };
+    let document = parse_with_options(yaml, &options)?.unwrap();
+    assert_eq!(
+        document.root.get("key").unwrap().comment,
+        Some("the first group spread over two lines\n\nthe second group".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn to_value_distinguishes_floats_from_integers() -> Result<()> {
+    let document = parse("float: 3.0\ninteger: 3")?.unwrap();
+    assert_eq!(
+        document.root.get("float").unwrap().value,
+        ScalarType::Float(3.0)
+    );
+    assert_eq!(
+        document.root.get("integer").unwrap().value,
+        ScalarType::Integer(3)
+    );
+
+    let value = document.to_value();
+    assert!(value["float"].is_f64());
+    assert!(value["integer"].is_i64());
+    assert_eq!(value.to_string(), r#"{"float":3.0,"integer":3}"#);
+
+    Ok(())
+}
+
+#[test]
+fn parse_multi_byte_unicode() -> Result<()> {
+    let yaml =
+        "# 説明 ✨\ndouble: \"日本語 😀\"\nsingle: '日本語 😀'\nplain: 日本語 😀 # 末尾 🎉\n";
+
+    let document = parse(yaml)?.unwrap();
+    let value = |key: &str| document.root.get(key).unwrap();
+    assert_eq!(value("double").value, ScalarType::String("日本語 😀"));
+    assert_eq!(value("double").comment, Some("説明 ✨".to_string()));
+    assert_eq!(value("single").value, ScalarType::String("日本語 😀"));
+    assert_eq!(value("plain").value, ScalarType::String("日本語 😀"));
+    assert_eq!(value("plain").comment, Some("末尾 🎉".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn parse_alias_of_anchored_mapping_and_sequence() -> Result<()> {
+    let yaml = r#"
+        defaults: &defaults
+          image: nginx
+          resources:
+            cpu: 100m
+        hosts: &hosts
+          - example.com
+          - www.example.com
+        web:
+          settings: *defaults
+          domains: *hosts
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let root = &document.root;
+    assert_eq!(
+        root.get_path("web.settings").unwrap().value,
+        root.get("defaults").unwrap().value
+    );
+    assert_eq!(
+        root.get_path("web.settings.resources.cpu").unwrap().value,
+        ScalarType::String("100m")
+    );
+
+    match &root.get_path("web.domains").unwrap().value {
+        ScalarType::List(items) => {
+            assert_eq!(items.len(), 2);
+            assert_eq!(items[1].value, ScalarType::String("www.example.com"));
+        }
+        _ => panic!("alias should resolve to a list scalar"),
+    }
+
+    Ok(())
+}
+
+fn keys<'a>(scalar: &'a Scalar<'_>) -> Vec<&'a str> {
+    match &scalar.value {
+        ScalarType::Map(map) => map.iter().map(|entry| entry.key).collect(),
+        _ => panic!("value should be a map"),
+    }
+}
+
+#[test]
+fn parse_merge_key() -> Result<()> {
+    let yaml = r#"
+        defaults: &defaults
+          image: nginx
+          replicas: 1
+        web:
+          name: web
+          <<: *defaults
+          replicas: 3
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let web = document.root.get("web").unwrap();
+    assert_eq!(keys(web), vec!["name", "image", "replicas"]);
+    assert_eq!(web.get("image").unwrap().value, ScalarType::String("nginx"));
+    assert_eq!(web.get("replicas").unwrap().value, ScalarType::Integer(3));
+    assert_eq!(web.get("image").unwrap().alias, Some("defaults"));
+
+    Ok(())
+}
+
+#[test]
+fn parse_merge_key_sequence() -> Result<()> {
+    let yaml = r#"
+        first: &first {x: 1, y: 1}
+        second: &second {y: 2, z: 2}
+        merged:
+          <<: [*first, *second]
+          z: 3
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let merged = document.root.get("merged").unwrap();
+    assert_eq!(keys(merged), vec!["x", "y", "z"]);
+    assert_eq!(merged.get("y").unwrap().value, ScalarType::Integer(1));
+    assert_eq!(merged.get("z").unwrap().value, ScalarType::Integer(3));
+
+    Ok(())
+}
+
+#[test]
+fn parse_alias_of_anchor_that_merges_another() -> Result<()> {
+    let yaml = r#"
+        base: &b {x: 1}
+        derived: &d {<<: *b, y: 2}
+        copy: *d
+        extended:
+          <<: *d
+          z: 3
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let root = &document.root;
+    let copy = root.get("copy").unwrap();
+    assert_eq!(keys(copy), vec!["x", "y"]);
+    assert_eq!(copy.get("x").unwrap().value, ScalarType::Integer(1));
+    assert_eq!(copy.get("y").unwrap().value, ScalarType::Integer(2));
+
+    let extended = root.get("extended").unwrap();
+    assert_eq!(keys(extended), vec!["x", "y", "z"]);
+    assert_eq!(extended.get("x").unwrap().value, ScalarType::Integer(1));
+
+    Ok(())
+}
+
+#[test]
+fn parse_definitions_of_nested_anchors_in_order() -> Result<()> {
+    let yaml = r#"
+        server: &server
+          port: &port 8080
+          tls: &tls
+            cert: &cert server.pem
+        replicas: &replicas 3
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let names: Vec<&str> = document
+        .definitions
+        .iter()
+        .map(|definition| definition.name)
+        .collect();
+    assert_eq!(names, vec!["server", "port", "tls", "cert", "replicas"]);
+    assert_eq!(
+        document.definitions[1].value.value,
+        ScalarType::Integer(8080)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_merge_key_requires_a_map() {
+    let result = parse("web:\n  <<: 3\n");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("merge key << expects a map or a list of maps at line 2, column 3")
+    );
+}
+
+#[test]
+fn parse_quoted_merge_key_is_a_key() -> Result<()> {
+    let document = parse("web:\n  \"<<\": 3\n")?.unwrap();
+    assert_eq!(keys(document.root.get("web").unwrap()), vec!["<<"]);
+
+    Ok(())
+}
+
+#[test]
+fn structurally_eq_ignores_comments() -> Result<()> {
+    let commented =
+        parse("# the name\nname: api # trailing\nports: [80] # @default -- 8080")?.unwrap();
+    let plain = parse("name: api\nports:\n  - 80")?.unwrap();
+    assert_ne!(commented.root, plain.root);
+    assert!(commented.structurally_eq(&plain));
+    assert!(
+        commented
+            .root
+            .get("name")
+            .unwrap()
+            .structurally_eq(plain.root.get("name").unwrap())
+    );
+
+    let changed = parse("name: web\nports: [80]")?.unwrap();
+    assert!(!commented.structurally_eq(&changed));
+
+    Ok(())
+}
+
+#[test]
+fn parse_trailing_comment_not_inherited_by_next_sibling() -> Result<()> {
+    let yaml = r#"
+        first: 1 # describes only the first key
+        second: 2
+        nested:
+          a: "c|d" # describes only a
+          b:
+            c: 3
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let comment = |path: &str| document.root.get_path(path).unwrap().comment.clone();
+    assert_eq!(
+        comment("first"),
+        Some("describes only the first key".to_string())
+    );
+    assert_eq!(comment("second"), None);
+    assert_eq!(comment("nested.a"), Some("describes only a".to_string()));
+    assert_eq!(comment("nested.b"), None);
+    assert_eq!(comment("nested.b.c"), None);
+
+    Ok(())
+}
+
+#[test]
+fn parse_nested_empty_flow_collections() -> Result<()> {
+    let document = parse("config: {}\ntags: []")?.unwrap();
+    assert_eq!(
+        document.root.get("config").unwrap().value,
+        ScalarType::Map(vec![])
+    );
+    assert_eq!(
+        document.root.get("tags").unwrap().value,
+        ScalarType::List(vec![])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn sort_maps_sorts_nested_keys_and_keeps_list_order() -> Result<()> {
+    let mut document = parse("zeta:\n  b: 1\n  a: 2\nalpha:\n  - z\n  - y\n")?.unwrap();
+    document.sort_maps();
+
+    fn keys<'a>(scalar: &Scalar<'a>) -> Vec<&'a str> {
+        match &scalar.value {
+            ScalarType::Map(map) => map.iter().map(|entry| entry.key).collect(),
+            _ => vec![],
+        }
+    }
+    assert_eq!(keys(&document.root), ["alpha", "zeta"]);
+    assert_eq!(keys(document.root.get("zeta").unwrap()), ["a", "b"]);
+    assert_eq!(
+        document.root.get("alpha").unwrap().value,
+        ScalarType::List(vec![
+            Scalar::new(ScalarType::String("z")),
+            Scalar::new(ScalarType::String("y")),
+        ])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_reserved_words_as_string_keys() -> Result<()> {
+    let document = parse("true: enabled\nnull: nothing\n0x1: hex\n")?.unwrap();
+    match document.root.value {
+        ScalarType::Map(ref map) => {
+            let keys: Vec<&str> = map.iter().map(|entry| entry.key).collect();
+            assert_eq!(keys, ["true", "null", "0x1"]);
+        }
+        _ => panic!("root node should contain a map scalar"),
+    }
+    assert_eq!(
+        document.root.get("true").unwrap().value,
+        ScalarType::String("enabled")
+    );
+    assert_eq!(
+        document.root.to_value(),
+        serde_json::json!({"true": "enabled", "null": "nothing", "0x1": "hex"})
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_divider_comments_are_ignored() -> Result<()> {
+    let yaml = "####\nfirst: 1\n##########\n# the second value\n##########\nsecond: 2\n";
+    let document = parse(yaml)?.unwrap();
+
+    assert_eq!(document.root.get("first").unwrap().comment, None);
+    assert_eq!(
+        document.root.get("second").unwrap().comment,
+        Some("the second value".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_long_single_line_flow_document() -> Result<()> {
+    let entries: Vec<String> = (0..2000)
+        .map(|n| format!("key{n}: {{nested: [1, 2, 3]}}"))
+        .collect();
+    let yaml = format!("{{{}}} # the settings", entries.join(", "));
+
+    let document = parse(&yaml)?.unwrap();
+    assert_eq!(document.root.comment, Some("the settings".to_string()));
+    assert_eq!(document.root.get("key0").unwrap().comment, None);
+    assert_eq!(
+        document.root.get_path("key1999.nested.2").unwrap().value,
+        ScalarType::Integer(3)
+    );
+    assert_eq!(
+        document.root.get_path("key1999.nested.2").unwrap().comment,
+        None
+    );
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "cli")]
+
+use anyhow::{Ok, Result};
+use yam::{markdown::RenderOptions, parser::parse, properties::render_properties};
+
+#[test]
+fn render_properties_escapes_values() -> Result<()> {
+    let yaml = r#"
+        # the database to connect to
+        url: "jdbc:mysql://db?ssl=true"
+        key with space: value
+        ports: [80, 443]
+        name: café
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let output = render_properties(&document, &RenderOptions::default())?;
+
+    assert_eq!(
+        output,
+        "# the database to connect to\n\
+         url=jdbc\\:mysql\\://db?ssl\\=true\n\
+         key\\ with\\ space=value\n\
+         ports.0=80\n\
+         ports.1=443\n\
+         name=caf\\u00e9\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn render_properties_notes_rows_left_out() -> Result<()> {
+    let document = parse("a: 1\nb: 2\nc: 3\n")?.unwrap();
+    let options = RenderOptions {
+        max_rows: Some(1),
+        ..Default::default()
+    };
+    let output = render_properties(&document, &options)?;
+
+    assert_eq!(output, "a=1\n# (truncated, 2 rows omitted)\n");
+    Ok(())
+}
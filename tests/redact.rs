@@ -0,0 +1,75 @@
+use anyhow::{Ok, Result};
+use yam::{
+    markdown::{IndexStyle, RenderOptions},
+    parser::{ScalarType, parse},
+    redact::{compile_patterns, redact_document},
+};
+
+#[test]
+fn redact_document_masks_matching_paths() -> Result<()> {
+    let yaml = r#"
+        database:
+          host: db.example.com
+          # the database password
+          password: hunter2
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let patterns = compile_patterns(&[".*password.*".to_string()])?;
+    let redacted = redact_document(&document, &patterns, &RenderOptions::default());
+
+    let database = match &redacted.root.value {
+        ScalarType::Map(map) => match &map[0].value.value {
+            ScalarType::Map(map) => map.clone(),
+            _ => panic!("child node should contain a map scalar"),
+        },
+        _ => panic!("root node should contain a map scalar"),
+    };
+
+    assert_eq!(
+        database[0].value.value,
+        ScalarType::String("db.example.com")
+    );
+    assert_eq!(database[1].value.value, ScalarType::String("***"));
+    assert_eq!(
+        database[1].value.comment,
+        Some("the database password".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn redact_patterns_match_the_entire_path() -> Result<()> {
+    let document = parse("password: hunter2")?.unwrap();
+    let patterns = compile_patterns(&["pass".to_string()])?;
+    let redacted = redact_document(&document, &patterns, &RenderOptions::default());
+
+    assert_eq!(redacted.root, document.root);
+
+    Ok(())
+}
+
+#[test]
+fn redact_patterns_match_list_items_as_rows_name_them() -> Result<()> {
+    let document = parse("tokens:\n  - abc\n  - def\n")?.unwrap();
+    let patterns = compile_patterns(&[r"tokens\[1\]".to_string()])?;
+    let options = RenderOptions {
+        index_style: IndexStyle::Bracket,
+        index_base: 1,
+        ..Default::default()
+    };
+    let redacted = redact_document(&document, &patterns, &options);
+
+    let tokens = redacted.root.get("tokens").unwrap();
+    assert_eq!(
+        tokens.get_path("0").unwrap().value,
+        ScalarType::String("***")
+    );
+    assert_eq!(
+        tokens.get_path("1").unwrap().value,
+        ScalarType::String("def")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anyhow::{Ok, Result};
+use yam::{
+    parser::parse,
+    report::{Summary, count_types, render_type_report},
+};
+
+#[test]
+fn count_types_tallies_leaf_values() -> Result<()> {
+    let yaml = r#"
+        name: api
+        image: nginx
+        replicas: 3
+        debug: false
+        ports: [80, 443]
+        annotations:
+          team: platform
+          owner:
+        "#;
+    let document = parse(yaml)?.unwrap();
+
+    let report = render_type_report(&count_types(&document));
+    assert_eq!(
+        report,
+        "### Types\n\nstrings: 3, integers: 3, booleans: 1, nulls: 1\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn summary_counts_values_across_documents() -> Result<()> {
+    let mut summary = Summary::default();
+    for yaml in [
+        "# the name\nname: api\nports: [80, 443]",
+        "resources:\n  limits:\n    cpu: 1",
+    ] {
+        summary.add(&parse(yaml)?.unwrap());
+    }
+
+    assert_eq!(
+        summary.to_string(),
+        "yam: 4 values, 3 undocumented, depth 3"
+    );
+
+    Ok(())
+}
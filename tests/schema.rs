@@ -0,0 +1,54 @@
+use anyhow::{Ok, Result};
+use serde_json::json;
+use yam::{error::YamError, parser::parse, schema::validate_document};
+
+#[test]
+fn validate_document_reports_violation() -> Result<()> {
+    let yaml = r#"
+        service:
+          port: http
+        "#;
+
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "service": {
+                "type": "object",
+                "properties": {
+                    "port": { "type": "integer" }
+                }
+            }
+        }
+    });
+
+    let document = parse(yaml)?.unwrap();
+    let violations = validate_document(&document, &schema)?;
+
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].path, "service.port");
+    assert!(violations[0].message.contains("integer"));
+
+    Ok(())
+}
+
+#[test]
+fn validate_document_without_violations() -> Result<()> {
+    let document = parse("port: 8080")?.unwrap();
+    let schema = json!({ "properties": { "port": { "type": "integer" } } });
+
+    assert!(validate_document(&document, &schema)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn validate_document_invalid_schema_keeps_source() -> Result<()> {
+    let document = parse("replicas: 3")?.unwrap();
+    let schema = json!({ "type": "not-a-type" });
+
+    let error = validate_document(&document, &schema).unwrap_err();
+    assert!(matches!(error, YamError::Schema(_)));
+    assert!(std::error::Error::source(&error).is_some());
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "cli")]
+
+use anyhow::{Ok, Result};
+use yam::{markdown::RenderOptions, parser::parse, slack::render_slack};
+
+#[test]
+fn render_slack_mrkdwn() -> Result<()> {
+    let yaml = r#"
+        # the name of the user
+        name: truman
+        # shown when compared
+        # with another value
+        bound: <10 & >2
+        empty:
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let output = render_slack(&document, &RenderOptions::default())?;
+
+    assert_eq!(
+        output,
+        "*name*: truman\n_the name of the user_\n*bound*: &lt;10 &amp; &gt;2\n_shown when compared with another value_\n*empty*: null\n"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "cli")]
+
+use anyhow::{Ok, Result};
+use yam::{parser::parse, xml::render_xml};
+
+// Checks that every element is closed in the order it was opened, beneath a
+// single root, that no unescaped markup appears within text and that only
+// characters XML 1.0 allows are written
+fn assert_well_formed(xml: &str) {
+    assert!(
+        !xml.chars()
+            .any(|c| c < '\x20' && !matches!(c, '\t' | '\n' | '\r')),
+        "control character in {xml:?}"
+    );
+
+    let mut open: Vec<&str> = Vec::new();
+    let mut roots = 0;
+    let mut rest = xml;
+
+    while let Some(start) = rest.find('<') {
+        assert!(!rest[..start].contains('>'), "unescaped '>' in {xml}");
+        let end = rest[start..].find('>').expect("unterminated tag") + start;
+        let tag = &rest[start + 1..end];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            assert_eq!(open.pop(), Some(name), "mismatched closing tag");
+        } else {
+            let name = tag.split_whitespace().next().unwrap().trim_end_matches('/');
+            assert!(
+                name.starts_with(|c: char| c.is_alphabetic() || c == '_'),
+                "invalid tag name {name}"
+            );
+            if open.is_empty() {
+                roots += 1;
+            }
+            if !tag.ends_with('/') {
+                open.push(name);
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+
+    assert!(open.is_empty(), "unclosed tags {open:?}");
+    assert_eq!(roots, 1);
+}
+
+#[test]
+fn render_xml_simple_map() -> Result<()> {
+    let yaml = r#"
+        name: truman
+        age: 42
+        ports: [80, 443]
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let xml = render_xml(&document)?;
+
+    assert_well_formed(&xml);
+    assert_eq!(
+        xml,
+        r#"<document type="map">
+  <name type="string">truman</name>
+  <age type="integer">42</age>
+  <ports type="list">
+    <item type="integer">80</item>
+    <item type="integer">443</item>
+  </ports>
+</document>
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn render_xml_sanitizes_keys_and_escapes_text() -> Result<()> {
+    let yaml = r#"
+        8080: open
+        "a b:c": x<y & z
+        empty: {}
+        "#;
+
+    let document = parse(yaml)?.unwrap();
+    let xml = render_xml(&document)?;
+
+    assert_well_formed(&xml);
+    assert!(xml.contains(r#"<_8080 type="string">open</_8080>"#));
+    assert!(xml.contains(r#"<a_b_c type="string">x&lt;y &amp; z</a_b_c>"#));
+    assert!(xml.contains(r#"<empty type="map"/>"#));
+
+    Ok(())
+}
+
+#[test]
+fn render_xml_replaces_control_characters() -> Result<()> {
+    let document = parse(r#"bell: "ring\x07 \ttab""#)?.unwrap();
+    let xml = render_xml(&document)?;
+
+    assert_well_formed(&xml);
+    assert!(xml.contains("<bell type=\"string\">ring\u{fffd} \ttab</bell>"));
+
+    Ok(())
+}
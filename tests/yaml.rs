@@ -0,0 +1,67 @@
+#![cfg(feature = "cli")]
+
+use anyhow::{Ok, Result};
+use yam::{parser::parse, yaml::render_yaml};
+
+#[test]
+fn render_yaml_canonical_form() -> Result<()> {
+    let yaml = r#"
+# the service
+service:   {name: 'api',   port: 8080}
+# hosts to serve
+hosts: [ "example.com",   www.example.com ]
+version: "1.0"
+enabled: yes
+empty: ''
+ratio: 1.0
+matrix: [[1, 2], [{a: 1}]]
+"#;
+
+    let document = parse(yaml)?.unwrap();
+    let output = render_yaml(&document)?;
+    assert_eq!(
+        output,
+        r#"# the service
+service:
+  name: api
+  port: 8080
+# hosts to serve
+hosts:
+  - example.com
+  - www.example.com
+version: "1.0"
+enabled: yes
+empty: ""
+ratio: 1.0
+matrix:
+  - - 1
+    - 2
+  - - a: 1
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn render_yaml_round_trips() -> Result<()> {
+    let yaml = r#"
+# the name, written as a
+# multi-line description
+name: "null"
+# @default -- generated
+password:
+motd: "line one\nline two: with a colon"
+ports: [80, 443]
+timeout: 1e3
+"#;
+
+    let document = parse(yaml)?.unwrap();
+    let output = render_yaml(&document)?;
+    let reparsed = parse(&output)?.unwrap();
+
+    assert!(document.structurally_eq(&reparsed));
+    assert_eq!(reparsed.root, document.root);
+
+    Ok(())
+}